@@ -0,0 +1,628 @@
+pub mod backtest {
+    use crate::fees::fees::{charge, FeeModel, NoFee};
+    use crate::models::models::{
+        Fill, Instrument, Liquidity, Order, OrderType, Position, Side, TimeInForce,
+    };
+    use crate::ohlc::ohlc::OHLC;
+    use crate::slippage::slippage::{adjust, NoSlippage, SlippageModel};
+
+    /// A trading strategy driven by a backtest.
+    pub trait Strategy {
+        /// Called with each OHLC once it has closed.
+        fn on_bar(&mut self, ctx: &mut Context, ohlc: &OHLC);
+
+        /// Called for each fill, before `on_bar` for the OHLC the fill happened in.
+        fn on_fill(&mut self, _ctx: &mut Context, _fill: &Fill) {}
+    }
+
+    /// The account and order state a strategy can see and act on.
+    #[derive(Debug, Clone)]
+    pub struct Context {
+        instrument: Instrument,
+        cash: f64,
+        position: Position,
+        working: Vec<Order>,
+        submitted: Vec<Order>,
+        next_id: u64,
+        ts: u64,
+    }
+
+    impl Context {
+        fn new(instrument: Instrument, cash: f64) -> Self {
+            let position =
+                Position::new(instrument.symbol.clone()).with_multiplier(instrument.multiplier);
+
+            Context {
+                instrument,
+                cash,
+                position,
+                working: Vec::new(),
+                submitted: Vec::new(),
+                next_id: 1,
+                ts: 0,
+            }
+        }
+
+        /// Submit an order, returning its id.
+        ///
+        /// The order is stamped with the current timestamp and becomes eligible to fill from the
+        /// next OHLC. The symbol is ignored, as all orders are for the backtest instrument.
+        pub fn submit(&mut self, order: Order) -> u64 {
+            let id = self.next_id;
+            self.next_id += 1;
+
+            let mut order = order.with_id(id);
+            order.symbol = self.instrument.symbol.clone();
+            order.ts = self.ts;
+            self.submitted.push(order);
+
+            id
+        }
+
+        /// Cancel an open order, returning false if there was no such order.
+        pub fn cancel(&mut self, id: u64) -> bool {
+            let before = self.working.len() + self.submitted.len();
+            self.working.retain(|order| order.id != id);
+            self.submitted.retain(|order| order.id != id);
+            before != self.working.len() + self.submitted.len()
+        }
+
+        /// Cancel all open orders.
+        pub fn cancel_all(&mut self) {
+            self.working.clear();
+            self.submitted.clear();
+        }
+
+        /// All open orders.
+        pub fn orders(&self) -> Vec<&Order> {
+            self.working.iter().chain(&self.submitted).collect()
+        }
+
+        /// The instrument being traded.
+        pub fn instrument(&self) -> &Instrument {
+            &self.instrument
+        }
+
+        /// The current position.
+        pub fn position(&self) -> &Position {
+            &self.position
+        }
+
+        /// The current cash balance.
+        pub fn cash(&self) -> f64 {
+            self.cash
+        }
+
+        /// Cash plus the value of the position at a price.
+        pub fn equity(&self, price: f64) -> f64 {
+            self.cash + self.position.market_value(price)
+        }
+
+        /// The timestamp of the OHLC being processed.
+        pub fn ts(&self) -> u64 {
+            self.ts
+        }
+
+        fn apply(&mut self, fill: &Fill) {
+            self.cash -= fill.signed_quantity() * fill.price * self.instrument.multiplier;
+            self.cash -= fill.fee;
+            self.position.apply(fill);
+        }
+    }
+
+    /// The outcome of a backtest.
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct BacktestResult {
+        /// Equity at the close of each OHLC.
+        pub equity_curve: Vec<(u64, f64)>,
+        /// Every fill, in the order they happened.
+        pub fills: Vec<Fill>,
+        pub cash: f64,
+        pub position: Position,
+    }
+
+    /// Replays OHLC into a strategy, simulating order matching for a single instrument.
+    ///
+    /// Orders fill completely against the OHLC after the one they were submitted in. Market orders
+    /// fill at the open. Limit and stop orders fill at the open if it is already through their
+    /// price, otherwise at their price if the OHLC reaches it. A triggered stop limit order fills
+    /// at its trigger price if that is within its limit, and otherwise works as a limit order from
+    /// the next OHLC.
+    ///
+    /// Fills are charged fees by the fee model, which charges nothing by default. Fills that take
+    /// liquidity are moved by the slippage model, which has no slippage by default, but never
+    /// beyond an order's limit price or outside the OHLC.
+    #[derive(Debug, Clone)]
+    pub struct Backtest<F = NoFee, M = NoSlippage> {
+        instrument: Instrument,
+        initial_cash: f64,
+        fee_model: F,
+        slippage_model: M,
+    }
+
+    impl Backtest {
+        /// Return a new Backtest without fees or slippage.
+        pub fn new(instrument: Instrument, initial_cash: f64) -> Self {
+            Backtest {
+                instrument,
+                initial_cash,
+                fee_model: NoFee,
+                slippage_model: NoSlippage,
+            }
+        }
+    }
+
+    impl<F: FeeModel, M: SlippageModel> Backtest<F, M> {
+        /// Specify the fee model for a Backtest.
+        pub fn with_fee_model<G: FeeModel>(self, fee_model: G) -> Backtest<G, M> {
+            Backtest {
+                instrument: self.instrument,
+                initial_cash: self.initial_cash,
+                fee_model,
+                slippage_model: self.slippage_model,
+            }
+        }
+
+        /// Specify the slippage model for a Backtest.
+        pub fn with_slippage_model<N: SlippageModel>(self, slippage_model: N) -> Backtest<F, N> {
+            Backtest {
+                instrument: self.instrument,
+                initial_cash: self.initial_cash,
+                fee_model: self.fee_model,
+                slippage_model,
+            }
+        }
+
+        /// Run a strategy over OHLC sorted by timestamp.
+        pub fn run<S: Strategy + ?Sized>(
+            &mut self,
+            ohlcs: &[OHLC],
+            strategy: &mut S,
+        ) -> BacktestResult {
+            let mut ctx = Context::new(self.instrument.clone(), self.initial_cash);
+            let mut equity_curve = Vec::with_capacity(ohlcs.len());
+            let mut fills = Vec::new();
+            self.fee_model.reset();
+
+            for ohlc in ohlcs {
+                ctx.ts = ohlc.ts;
+
+                let working = std::mem::take(&mut ctx.working);
+                for order in working {
+                    if order.is_expired(ohlc.ts) {
+                        continue;
+                    }
+
+                    match match_order(&order, ohlc) {
+                        Match::Filled(price) => {
+                            let liquidity = liquidity(&order, price, ohlc);
+                            let price = match liquidity {
+                                Liquidity::Maker => price,
+                                Liquidity::Taker => within_limit(
+                                    &order,
+                                    adjust(
+                                        &self.slippage_model,
+                                        order.side,
+                                        price,
+                                        order.quantity,
+                                        ohlc,
+                                    ),
+                                ),
+                            };
+                            let fill = charge(
+                                &mut self.fee_model,
+                                Fill::from_order(&order, price, order.quantity, ohlc)
+                                    .with_liquidity(liquidity),
+                                &self.instrument,
+                            );
+                            ctx.apply(&fill);
+                            strategy.on_fill(&mut ctx, &fill);
+                            fills.push(fill);
+                        }
+                        Match::Triggered(limit) => {
+                            if !expires_unfilled(&order) {
+                                let mut order = order;
+                                order.order_type = OrderType::Limit { price: limit };
+                                ctx.working.push(order);
+                            }
+                        }
+                        Match::Unfilled => {
+                            if !expires_unfilled(&order) {
+                                ctx.working.push(order);
+                            }
+                        }
+                    }
+                }
+
+                strategy.on_bar(&mut ctx, ohlc);
+
+                let submitted = std::mem::take(&mut ctx.submitted);
+                ctx.working.extend(submitted);
+
+                equity_curve.push((ohlc.ts, ctx.equity(ohlc.close)));
+            }
+
+            BacktestResult {
+                equity_curve,
+                fills,
+                cash: ctx.cash,
+                position: ctx.position,
+            }
+        }
+    }
+
+    /// The result of matching an order against an OHLC.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    enum Match {
+        Filled(f64),
+        /// A stop limit order was triggered but its limit was not reached.
+        Triggered(f64),
+        Unfilled,
+    }
+
+    /// Day, immediate or cancel and fill or kill orders only get one OHLC to fill in.
+    fn expires_unfilled(order: &Order) -> bool {
+        matches!(
+            order.time_in_force,
+            TimeInForce::Day | TimeInForce::Ioc | TimeInForce::Fok
+        )
+    }
+
+    /// Limit orders that fill at their price, rather than the open, were resting and added
+    /// liquidity.
+    fn liquidity(order: &Order, price: f64, ohlc: &OHLC) -> Liquidity {
+        match order.order_type {
+            OrderType::Limit { .. } if price != ohlc.open => Liquidity::Maker,
+            _ => Liquidity::Taker,
+        }
+    }
+
+    /// Caps a price at the order's limit, if it has one.
+    fn within_limit(order: &Order, price: f64) -> f64 {
+        let limit = match order.order_type {
+            OrderType::Limit { price } => price,
+            OrderType::StopLimit { limit, .. } => limit,
+            OrderType::Market | OrderType::Stop { .. } => return price,
+        };
+
+        match order.side {
+            Side::Buy => price.min(limit),
+            Side::Sell => price.max(limit),
+        }
+    }
+
+    fn match_order(order: &Order, ohlc: &OHLC) -> Match {
+        let buy = order.side == Side::Buy;
+
+        let filled = |price: Option<f64>| price.map_or(Match::Unfilled, Match::Filled);
+
+        match order.order_type {
+            OrderType::Market => Match::Filled(ohlc.open),
+            OrderType::Limit { price } => filled(limit_price(buy, price, ohlc)),
+            OrderType::Stop { price } => filled(stop_price(buy, price, ohlc)),
+            OrderType::StopLimit { stop, limit } => match stop_price(buy, stop, ohlc) {
+                None => Match::Unfilled,
+                Some(trigger) => {
+                    let within = if buy {
+                        trigger <= limit
+                    } else {
+                        trigger >= limit
+                    };
+                    if within {
+                        Match::Filled(trigger)
+                    } else {
+                        Match::Triggered(limit)
+                    }
+                }
+            },
+        }
+    }
+
+    fn limit_price(buy: bool, price: f64, ohlc: &OHLC) -> Option<f64> {
+        if buy {
+            if ohlc.open <= price {
+                Some(ohlc.open)
+            } else if ohlc.low <= price {
+                Some(price)
+            } else {
+                None
+            }
+        } else if ohlc.open >= price {
+            Some(ohlc.open)
+        } else if ohlc.high >= price {
+            Some(price)
+        } else {
+            None
+        }
+    }
+
+    fn stop_price(buy: bool, price: f64, ohlc: &OHLC) -> Option<f64> {
+        if buy {
+            if ohlc.open >= price {
+                Some(ohlc.open)
+            } else if ohlc.high >= price {
+                Some(price)
+            } else {
+                None
+            }
+        } else if ohlc.open <= price {
+            Some(ohlc.open)
+        } else if ohlc.low <= price {
+            Some(price)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::fees::fees::MakerTaker;
+        use crate::slippage::slippage::FixedBps;
+
+        fn ohlcs() -> Vec<OHLC> {
+            vec![
+                OHLC::new(100.0, 102.0, 99.0, 101.0, 1000),
+                OHLC::new(101.0, 105.0, 100.0, 104.0, 2000),
+                OHLC::new(104.0, 106.0, 97.0, 98.0, 3000),
+                OHLC::new(98.0, 99.0, 95.0, 96.0, 4000),
+            ]
+        }
+
+        /// Submits the scripted orders on the matching bar index.
+        struct Scripted {
+            orders: Vec<(usize, Order)>,
+            bar: usize,
+            fills: usize,
+        }
+
+        impl Strategy for Scripted {
+            fn on_bar(&mut self, ctx: &mut Context, _ohlc: &OHLC) {
+                for (_, order) in self.orders.iter().filter(|(i, _)| *i == self.bar) {
+                    ctx.submit(order.clone());
+                }
+                self.bar += 1;
+            }
+
+            fn on_fill(&mut self, _ctx: &mut Context, _fill: &Fill) {
+                self.fills += 1;
+            }
+        }
+
+        fn run(orders: Vec<(usize, Order)>) -> (BacktestResult, usize) {
+            let mut strategy = Scripted {
+                orders,
+                bar: 0,
+                fills: 0,
+            };
+            let result = Backtest::new(Instrument::new("TEST", "USD"), 10_000.0)
+                .run(&ohlcs(), &mut strategy);
+            (result, strategy.fills)
+        }
+
+        #[test]
+        fn test_match_order() {
+            let ohlc = OHLC::new(100.0, 105.0, 95.0, 102.0, 1000);
+
+            let test_cases = vec![
+                (
+                    "market",
+                    Order::market("T", Side::Buy, 1.0, 0),
+                    Match::Filled(100.0),
+                ),
+                (
+                    "buy limit at open",
+                    Order::limit("T", Side::Buy, 1.0, 101.0, 0),
+                    Match::Filled(100.0),
+                ),
+                (
+                    "buy limit in range",
+                    Order::limit("T", Side::Buy, 1.0, 97.0, 0),
+                    Match::Filled(97.0),
+                ),
+                (
+                    "buy limit missed",
+                    Order::limit("T", Side::Buy, 1.0, 94.0, 0),
+                    Match::Unfilled,
+                ),
+                (
+                    "sell limit in range",
+                    Order::limit("T", Side::Sell, 1.0, 104.0, 0),
+                    Match::Filled(104.0),
+                ),
+                (
+                    "sell limit at open",
+                    Order::limit("T", Side::Sell, 1.0, 99.0, 0),
+                    Match::Filled(100.0),
+                ),
+                (
+                    "buy stop in range",
+                    Order::stop("T", Side::Buy, 1.0, 103.0, 0),
+                    Match::Filled(103.0),
+                ),
+                (
+                    "buy stop at open",
+                    Order::stop("T", Side::Buy, 1.0, 99.0, 0),
+                    Match::Filled(100.0),
+                ),
+                (
+                    "sell stop missed",
+                    Order::stop("T", Side::Sell, 1.0, 94.0, 0),
+                    Match::Unfilled,
+                ),
+                (
+                    "buy stop limit filled",
+                    Order::stop_limit("T", Side::Buy, 1.0, 103.0, 104.0, 0),
+                    Match::Filled(103.0),
+                ),
+                (
+                    "buy stop limit triggered",
+                    Order::stop_limit("T", Side::Buy, 1.0, 99.0, 98.0, 0),
+                    Match::Triggered(98.0),
+                ),
+                (
+                    "sell stop limit missed",
+                    Order::stop_limit("T", Side::Sell, 1.0, 90.0, 89.0, 0),
+                    Match::Unfilled,
+                ),
+            ];
+
+            for (name, order, expected) in test_cases {
+                assert_eq!(
+                    match_order(&order, &ohlc),
+                    expected,
+                    "Test case '{}' failed",
+                    name
+                );
+            }
+        }
+
+        #[test]
+        fn test_run_round_trip() {
+            let (result, fills) = run(vec![
+                (0, Order::market("TEST", Side::Buy, 10.0, 0)),
+                (1, Order::limit("TEST", Side::Sell, 10.0, 105.0, 0)),
+            ]);
+
+            assert_eq!(fills, 2, "Strategy should be told about each fill");
+            assert_eq!(result.fills.len(), 2);
+            assert_eq!(
+                result.fills[0].price, 101.0,
+                "Market order fills at next open"
+            );
+            assert_eq!(result.fills[0].ts, 2000);
+            assert_eq!(result.fills[1].price, 105.0);
+            assert_eq!(result.fills[1].ts, 3000);
+            assert_eq!(result.position.realized_pnl, 40.0);
+            assert!(result.position.is_flat());
+            assert_eq!(result.cash, 10_040.0);
+            assert_eq!(
+                result.equity_curve,
+                vec![
+                    (1000, 10_000.0),
+                    (2000, 10_030.0),
+                    (3000, 10_040.0),
+                    (4000, 10_040.0)
+                ]
+            );
+        }
+
+        #[test]
+        fn test_run_fees() {
+            let mut strategy = Scripted {
+                orders: vec![
+                    (0, Order::market("TEST", Side::Buy, 10.0, 0)),
+                    (1, Order::limit("TEST", Side::Sell, 10.0, 105.0, 0)),
+                ],
+                bar: 0,
+                fills: 0,
+            };
+            let fee_model = MakerTaker {
+                maker: -0.001,
+                taker: 0.002,
+            };
+
+            let result = Backtest::new(Instrument::new("TEST", "USD"), 10_000.0)
+                .with_fee_model(fee_model)
+                .run(&ohlcs(), &mut strategy);
+
+            let summary: Vec<(Liquidity, f64)> = result
+                .fills
+                .iter()
+                .map(|fill| (fill.liquidity, fill.fee))
+                .collect();
+            assert_eq!(
+                summary,
+                vec![
+                    (Liquidity::Taker, 1010.0 * 0.002),
+                    (Liquidity::Maker, -1.05)
+                ]
+            );
+            assert!((result.cash - (10_040.0 - 2.02 + 1.05)).abs() < 1e-9);
+            assert_eq!(result.position.fees, 1010.0 * 0.002 - 1.05);
+        }
+
+        #[test]
+        fn test_run_slippage() {
+            let mut strategy = Scripted {
+                orders: vec![
+                    (0, Order::market("TEST", Side::Buy, 10.0, 0)),
+                    (1, Order::limit("TEST", Side::Sell, 10.0, 105.0, 0)),
+                    (2, Order::limit("TEST", Side::Buy, 10.0, 98.1, 0)),
+                    (2, Order::market("TEST", Side::Sell, 10.0, 0)),
+                ],
+                bar: 0,
+                fills: 0,
+            };
+
+            let result = Backtest::new(Instrument::new("TEST", "USD"), 10_000.0)
+                .with_slippage_model(FixedBps(20.0))
+                .run(&ohlcs(), &mut strategy);
+
+            let prices: Vec<f64> = result.fills.iter().map(|fill| fill.price).collect();
+            assert_eq!(prices.len(), 4);
+            assert!((prices[0] - 101.202).abs() < 1e-9, "Market buy slips up");
+            assert_eq!(prices[1], 105.0, "Resting limit does not slip");
+            assert_eq!(
+                prices[2], 98.1,
+                "Marketable limit slips no further than its limit"
+            );
+            assert_eq!(prices[3], 97.804, "Market sell slips down");
+        }
+
+        #[test]
+        fn test_run_time_in_force() {
+            let test_cases = vec![
+                ("gtc fills later", TimeInForce::Gtc, 1),
+                ("day expires", TimeInForce::Day, 0),
+                ("ioc expires", TimeInForce::Ioc, 0),
+                ("gtd before expiry", TimeInForce::Gtd(3000), 1),
+                ("gtd expired", TimeInForce::Gtd(2500), 0),
+            ];
+
+            for (name, time_in_force, expected) in test_cases {
+                // Only reachable on the third bar.
+                let order =
+                    Order::limit("TEST", Side::Buy, 1.0, 97.5, 0).with_time_in_force(time_in_force);
+                let (result, _) = run(vec![(0, order)]);
+
+                assert_eq!(result.fills.len(), expected, "Test case '{}' failed", name);
+            }
+        }
+
+        #[test]
+        fn test_run_stop_limit_becomes_limit() {
+            let (result, _) = run(vec![(
+                0,
+                Order::stop_limit("TEST", Side::Buy, 1.0, 104.0, 103.0, 0),
+            )]);
+
+            // Triggered at 104 on the second bar, then fills at its 103 limit on the third.
+            assert_eq!(result.fills.len(), 1);
+            assert_eq!(result.fills[0].price, 103.0);
+            assert_eq!(result.fills[0].ts, 3000);
+        }
+
+        #[test]
+        fn test_cancel() {
+            struct Canceller;
+
+            impl Strategy for Canceller {
+                fn on_bar(&mut self, ctx: &mut Context, _ohlc: &OHLC) {
+                    if ctx.ts() == 1000 {
+                        let id = ctx.submit(Order::market("TEST", Side::Buy, 1.0, 0));
+                        assert_eq!(ctx.orders().len(), 1);
+                        assert!(ctx.cancel(id));
+                        assert!(!ctx.cancel(id));
+                    }
+                }
+            }
+
+            let result =
+                Backtest::new(Instrument::new("TEST", "USD"), 100.0).run(&ohlcs(), &mut Canceller);
+
+            assert!(result.fills.is_empty());
+            assert_eq!(result.cash, 100.0);
+        }
+    }
+}
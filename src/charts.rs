@@ -0,0 +1,475 @@
+pub mod charts {
+    use crate::indicators::indicators::atr;
+    use crate::ohlc::ohlc::OHLC;
+
+    /// The prices of each OHLC a chart is built from.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum Source {
+        Close,
+        /// The low then the high for a bullish OHLC, otherwise the high then the low.
+        HighLow,
+    }
+
+    impl Source {
+        pub(crate) fn prices(&self, ohlc: &OHLC) -> Vec<f64> {
+            match self {
+                Source::Close => vec![ohlc.close],
+                Source::HighLow if ohlc.direction() > 0 => vec![ohlc.low, ohlc.high],
+                Source::HighLow => vec![ohlc.high, ohlc.low],
+            }
+        }
+    }
+
+    /// The box size of a Renko chart.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub enum BoxSize {
+        Fixed(f64),
+        /// The ATR over this period at the first OHLC it can be calculated for. The chart starts
+        /// from the close of that OHLC, so no brick depends on later prices.
+        Atr(usize),
+    }
+
+    /// Volume seen since the last emitted record.
+    ///
+    /// The total goes to the first record an OHLC completes, and any more records completed by
+    /// the same OHLC get zero.
+    #[derive(Debug, Clone, Default)]
+    struct Volume {
+        total: Option<f64>,
+        seen: bool,
+    }
+
+    impl Volume {
+        fn add(&mut self, ohlc: &OHLC) {
+            if let Some(vol) = ohlc.vol {
+                self.total = Some(self.total.unwrap_or_default() + vol);
+                self.seen = true;
+            }
+        }
+
+        fn take(&mut self) -> Option<f64> {
+            match self.total.take() {
+                Some(total) => Some(total),
+                None if self.seen => Some(0.0),
+                None => None,
+            }
+        }
+
+        fn clear(&mut self) {
+            self.total = None;
+            self.seen = false;
+        }
+    }
+
+    fn record(open: f64, close: f64, ts: u64, vol: Option<f64>) -> OHLC {
+        OHLC {
+            open,
+            high: open.max(close),
+            low: open.min(close),
+            close,
+            vol,
+            ts,
+        }
+    }
+
+    /// The smallest box size or range allowed, as a fraction of the price. Anything smaller
+    /// could take millions of bricks or bars to follow a single move.
+    const MIN_SIZE_FRACTION: f64 = 1e-6;
+
+    fn valid_size(size: f64, price: f64) -> bool {
+        size.is_finite() && size > 0.0 && size >= price.abs() * MIN_SIZE_FRACTION
+    }
+
+    /// Builds Renko bricks from OHLC one at a time.
+    ///
+    /// The first price is the base. A brick is added each time price moves a box beyond the last
+    /// brick, and a reversal needs a move of two boxes from the last close. A box size that is not
+    /// positive, or is less than a millionth of the price, produces no bricks.
+    #[derive(Debug, Clone)]
+    pub struct Renko {
+        box_size: f64,
+        source: Source,
+        /// The top and bottom of the last brick, or the base twice before the first brick.
+        last: Option<(f64, f64)>,
+        volume: Volume,
+    }
+
+    impl Renko {
+        /// Return a new Renko.
+        pub fn new(box_size: f64, source: Source) -> Self {
+            Renko {
+                box_size,
+                source,
+                last: None,
+                volume: Volume::default(),
+            }
+        }
+
+        /// Add an OHLC, returning the bricks it completed, stamped with its timestamp.
+        pub fn push(&mut self, ohlc: &OHLC) -> Vec<OHLC> {
+            let mut bricks = Vec::new();
+            if !valid_size(self.box_size, ohlc.close) {
+                return bricks;
+            }
+            self.volume.add(ohlc);
+
+            for price in self.source.prices(ohlc) {
+                let (mut top, mut bottom) = *self.last.get_or_insert((price, price));
+
+                loop {
+                    if price >= top + self.box_size {
+                        bricks.push(record(
+                            top,
+                            top + self.box_size,
+                            ohlc.ts,
+                            self.volume.take(),
+                        ));
+                        bottom = top;
+                        top += self.box_size;
+                    } else if price <= bottom - self.box_size {
+                        let close = bottom - self.box_size;
+                        bricks.push(record(bottom, close, ohlc.ts, self.volume.take()));
+                        top = bottom;
+                        bottom = close;
+                    } else {
+                        break;
+                    }
+                }
+
+                self.last = Some((top, bottom));
+            }
+
+            bricks
+        }
+
+        /// Discard all state, as if no OHLC had been seen.
+        pub fn reset(&mut self) {
+            self.last = None;
+            self.volume.clear();
+        }
+    }
+
+    /// Builds Renko bricks from a slice of OHLC.
+    ///
+    /// An ATR box size is taken from the first ATR value, and the chart is built from the OHLC
+    /// after it, starting at its close. Returns no bricks if the ATR cannot be calculated.
+    pub fn renko(ohlcs: &[OHLC], box_size: BoxSize, source: Source) -> Vec<OHLC> {
+        let (mut builder, ohlcs) = match box_size {
+            BoxSize::Fixed(size) => (Renko::new(size, source), ohlcs),
+            BoxSize::Atr(period) => {
+                let Some((index, size)) = atr(ohlcs, period)
+                    .into_iter()
+                    .enumerate()
+                    .find_map(|(i, value)| value.map(|size| (i, size)))
+                else {
+                    return Vec::new();
+                };
+
+                let base = ohlcs[index].close;
+                let mut builder = Renko::new(size, source);
+                builder.last = Some((base, base));
+                (builder, &ohlcs[index + 1..])
+            }
+        };
+
+        ohlcs.iter().flat_map(|ohlc| builder.push(ohlc)).collect()
+    }
+
+    /// Builds range bars from OHLC one at a time.
+    ///
+    /// A bar is completed when its high and low are `range` apart, closing at the extreme of
+    /// the range, and the next bar opens at that close. A range that is not positive, or is less
+    /// than a millionth of the price, produces no bars.
+    #[derive(Debug, Clone)]
+    pub struct RangeBars {
+        range: f64,
+        source: Source,
+        /// The open, high and low of the bar being built.
+        current: Option<(f64, f64, f64)>,
+        volume: Volume,
+    }
+
+    impl RangeBars {
+        /// Return a new RangeBars.
+        pub fn new(range: f64, source: Source) -> Self {
+            RangeBars {
+                range,
+                source,
+                current: None,
+                volume: Volume::default(),
+            }
+        }
+
+        /// Add an OHLC, returning the bars it completed, stamped with its timestamp.
+        pub fn push(&mut self, ohlc: &OHLC) -> Vec<OHLC> {
+            let mut bars = Vec::new();
+            if !valid_size(self.range, ohlc.close) {
+                return bars;
+            }
+            self.volume.add(ohlc);
+
+            for price in self.source.prices(ohlc) {
+                let (mut open, mut high, mut low) =
+                    *self.current.get_or_insert((price, price, price));
+
+                loop {
+                    let close = if price >= low + self.range {
+                        low + self.range
+                    } else if price <= high - self.range {
+                        high - self.range
+                    } else {
+                        break;
+                    };
+
+                    let mut bar = record(open, close, ohlc.ts, self.volume.take());
+                    bar.high = high.max(close);
+                    bar.low = low.min(close);
+                    bars.push(bar);
+
+                    (open, high, low) = (close, close, close);
+                }
+
+                self.current = Some((open, high.max(price), low.min(price)));
+            }
+
+            bars
+        }
+
+        /// Discard all state, as if no OHLC had been seen.
+        pub fn reset(&mut self) {
+            self.current = None;
+            self.volume.clear();
+        }
+    }
+
+    /// Builds range bars from a slice of OHLC. The final incomplete bar is not included.
+    pub fn range_bars(ohlcs: &[OHLC], range: f64, source: Source) -> Vec<OHLC> {
+        let mut builder = RangeBars::new(range, source);
+        ohlcs.iter().flat_map(|ohlc| builder.push(ohlc)).collect()
+    }
+
+    /// Builds line break lines from OHLC closes one at a time.
+    ///
+    /// A line is added when the close extends the last line. A reversal needs the close to pass
+    /// the extreme of the last `count` lines, and opens at the start of the last line. Zero lines
+    /// is treated as one.
+    #[derive(Debug, Clone)]
+    pub struct LineBreak {
+        count: usize,
+        base: Option<f64>,
+        lines: Vec<OHLC>,
+        volume: Volume,
+    }
+
+    impl LineBreak {
+        /// Return a new LineBreak.
+        pub fn new(count: usize) -> Self {
+            LineBreak {
+                count: count.max(1),
+                base: None,
+                lines: Vec::new(),
+                volume: Volume::default(),
+            }
+        }
+
+        /// Add an OHLC, returning the line it completed, stamped with its timestamp.
+        pub fn push(&mut self, ohlc: &OHLC) -> Option<OHLC> {
+            self.volume.add(ohlc);
+            let close = ohlc.close;
+
+            let open = match self.lines.last() {
+                None => {
+                    let base = *self.base.get_or_insert(close);
+                    (close != base).then_some(base)?
+                }
+                Some(last) => {
+                    let recent = &self.lines[self.lines.len().saturating_sub(self.count)..];
+                    let highest = recent.iter().map(|l| l.high).fold(f64::MIN, f64::max);
+                    let lowest = recent.iter().map(|l| l.low).fold(f64::MAX, f64::min);
+                    let rising = last.close > last.open;
+
+                    if (rising && close > last.close) || (!rising && close < last.close) {
+                        last.close
+                    } else if (rising && close < lowest) || (!rising && close > highest) {
+                        last.open
+                    } else {
+                        return None;
+                    }
+                }
+            };
+
+            let line = record(open, close, ohlc.ts, self.volume.take());
+            self.lines.push(line.clone());
+            Some(line)
+        }
+
+        /// Discard all state, as if no OHLC had been seen.
+        pub fn reset(&mut self) {
+            self.base = None;
+            self.lines.clear();
+            self.volume.clear();
+        }
+    }
+
+    /// Builds `count` line break lines from a slice of OHLC closes.
+    pub fn line_break(ohlcs: &[OHLC], count: usize) -> Vec<OHLC> {
+        let mut builder = LineBreak::new(count);
+        ohlcs.iter().filter_map(|ohlc| builder.push(ohlc)).collect()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn close(close: f64, ts: u64) -> OHLC {
+            OHLC::new(close, close, close, close, ts).with_volume(10.0)
+        }
+
+        fn line(open: f64, close: f64, ts: u64, vol: f64) -> OHLC {
+            record(open, close, ts, Some(vol))
+        }
+
+        #[test]
+        fn test_renko() {
+            let ohlcs = vec![
+                close(100.0, 1),
+                close(101.5, 2),
+                close(103.2, 3),
+                close(101.5, 4),
+                close(100.9, 5),
+                close(98.0, 6),
+            ];
+
+            let result = renko(&ohlcs, BoxSize::Fixed(1.0), Source::Close);
+
+            assert_eq!(
+                result,
+                vec![
+                    line(100.0, 101.0, 2, 20.0),
+                    line(101.0, 102.0, 3, 10.0),
+                    line(102.0, 103.0, 3, 0.0),
+                    line(102.0, 101.0, 5, 20.0),
+                    line(101.0, 100.0, 6, 10.0),
+                    line(100.0, 99.0, 6, 0.0),
+                    line(99.0, 98.0, 6, 0.0),
+                ]
+            );
+        }
+
+        #[test]
+        fn test_renko_high_low() {
+            let ohlcs = vec![
+                OHLC::new(100.0, 100.0, 100.0, 100.0, 1),
+                OHLC::new(100.0, 102.5, 99.0, 102.0, 2),
+                OHLC::new(102.0, 102.0, 96.5, 97.0, 3),
+            ];
+
+            let result = renko(&ohlcs, BoxSize::Fixed(2.0), Source::HighLow);
+
+            assert_eq!(
+                result,
+                vec![record(100.0, 102.0, 2, None), record(100.0, 98.0, 3, None),]
+            );
+        }
+
+        #[test]
+        fn test_renko_atr() {
+            // Each true range is 2 until the last three OHLC, which widen the ATR after the box
+            // size is fixed at 2.
+            let ohlcs: Vec<OHLC> = (0..9)
+                .map(|i| {
+                    let mid = 100.0 + i as f64;
+                    let spread = if i < 6 { 1.0 } else { 5.0 };
+                    OHLC::new(mid, mid + spread, mid - spread, mid, i + 1)
+                })
+                .collect();
+
+            let result = renko(&ohlcs, BoxSize::Atr(3), Source::Close);
+
+            // The ATR is first known at the fourth OHLC, so the chart starts from its close.
+            assert_eq!(
+                result,
+                vec![record(103.0, 105.0, 6, None), record(105.0, 107.0, 8, None)]
+            );
+            assert_eq!(renko(&ohlcs, BoxSize::Atr(10), Source::Close), vec![]);
+            assert_eq!(renko(&ohlcs, BoxSize::Fixed(0.0), Source::Close), vec![]);
+        }
+
+        #[test]
+        fn test_tiny_size() {
+            let ohlcs = vec![close(100.0, 1), close(200.0, 2)];
+
+            assert_eq!(renko(&ohlcs, BoxSize::Fixed(1e-9), Source::Close), vec![]);
+            assert_eq!(range_bars(&ohlcs, 1e-9, Source::Close), vec![]);
+            assert_eq!(renko(&ohlcs, BoxSize::Fixed(50.0), Source::Close).len(), 2);
+        }
+
+        #[test]
+        fn test_range_bars() {
+            let ohlcs = vec![
+                close(100.0, 1),
+                close(101.0, 2),
+                close(104.5, 3),
+                close(103.0, 4),
+                close(101.0, 5),
+            ];
+
+            let result = range_bars(&ohlcs, 2.0, Source::Close);
+
+            assert_eq!(
+                result,
+                vec![
+                    line(100.0, 102.0, 3, 30.0),
+                    line(102.0, 104.0, 3, 0.0),
+                    OHLC::new(104.0, 104.5, 102.5, 102.5, 5).with_volume(20.0),
+                ]
+            );
+            assert!(result.iter().all(|bar| bar.range() == 2.0));
+        }
+
+        #[test]
+        fn test_line_break() {
+            let closes = [
+                10.0, 10.0, 11.0, 12.0, 11.5, 13.0, 12.5, 10.5, 9.5, 11.0, 13.5,
+            ];
+            let ohlcs: Vec<OHLC> = closes
+                .iter()
+                .enumerate()
+                .map(|(i, &c)| close(c, i as u64 + 1))
+                .collect();
+
+            let result = line_break(&ohlcs, 3);
+
+            assert_eq!(
+                result,
+                vec![
+                    line(10.0, 11.0, 3, 30.0),
+                    line(11.0, 12.0, 4, 10.0),
+                    line(12.0, 13.0, 6, 20.0),
+                    line(12.0, 9.5, 9, 30.0),
+                    line(12.0, 13.5, 11, 20.0),
+                ]
+            );
+        }
+
+        #[test]
+        fn test_builders_reset() {
+            let ohlcs = [close(100.0, 1), close(103.0, 2), close(99.0, 3)];
+            let mut renko = Renko::new(1.0, Source::Close);
+            let mut range = RangeBars::new(1.0, Source::Close);
+            let mut lines = LineBreak::new(2);
+
+            let mut run = || {
+                let bricks: Vec<OHLC> = ohlcs.iter().flat_map(|o| renko.push(o)).collect();
+                let bars: Vec<OHLC> = ohlcs.iter().flat_map(|o| range.push(o)).collect();
+                let breaks: Vec<OHLC> = ohlcs.iter().filter_map(|o| lines.push(o)).collect();
+                renko.reset();
+                range.reset();
+                lines.reset();
+                (bricks, bars, breaks)
+            };
+
+            assert_eq!(run(), run());
+        }
+    }
+}
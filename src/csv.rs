@@ -0,0 +1,629 @@
+pub mod csv {
+    use crate::ohlc::ohlc::{OhlcError, OHLC};
+    use crate::timeframe::timeframe::{civil_from_days, days_from_civil, SECONDS_PER_DAY};
+    use crate::timestamp::timestamp::Unit;
+    use std::fmt;
+    use std::io;
+
+    const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+    /// How timestamps are written in a CSV file.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum TimestampFormat {
+        /// An integer count since the Unix epoch.
+        Epoch(Unit),
+        /// A UTC date or date time such as `2021-07-01T00:00:00Z`. Offsets are accepted when
+        /// reading, and `Z` is always written.
+        Iso8601,
+    }
+
+    /// Column names in a CSV file.
+    #[derive(Debug, PartialEq, Eq, Clone)]
+    pub struct Columns {
+        pub open: String,
+        pub high: String,
+        pub low: String,
+        pub close: String,
+        /// The volume column, if the file has one.
+        pub volume: Option<String>,
+        pub ts: String,
+    }
+
+    impl Default for Columns {
+        fn default() -> Self {
+            Columns {
+                open: "open".to_string(),
+                high: "high".to_string(),
+                low: "low".to_string(),
+                close: "close".to_string(),
+                volume: Some("volume".to_string()),
+                ts: "ts".to_string(),
+            }
+        }
+    }
+
+    /// Options for reading and writing OHLC as CSV.
+    #[derive(Debug, PartialEq, Eq, Clone)]
+    pub struct Opts {
+        pub columns: Columns,
+        pub format: TimestampFormat,
+        /// The unit of the OHLC timestamps.
+        pub unit: Unit,
+        pub delimiter: u8,
+    }
+
+    impl Default for Opts {
+        fn default() -> Self {
+            Opts {
+                columns: Columns::default(),
+                format: TimestampFormat::Epoch(Unit::Seconds),
+                unit: Unit::Seconds,
+                delimiter: b',',
+            }
+        }
+    }
+
+    /// The reason a CSV row could not be read.
+    #[derive(Debug, PartialEq, Clone)]
+    pub enum ErrorKind {
+        MissingColumn(String),
+        InvalidNumber { column: String, value: String },
+        InvalidTimestamp(String),
+        Invalid(Vec<OhlcError>),
+        Csv(String),
+    }
+
+    /// An error reading a CSV file, with the line it occurred on. The header is line 1.
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct CsvError {
+        pub line: u64,
+        pub kind: ErrorKind,
+    }
+
+    impl fmt::Display for CsvError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "line {}: ", self.line)?;
+
+            match &self.kind {
+                ErrorKind::MissingColumn(column) => write!(f, "missing column '{}'", column),
+                ErrorKind::InvalidNumber { column, value } => {
+                    write!(f, "invalid number '{}' in column '{}'", value, column)
+                }
+                ErrorKind::InvalidTimestamp(value) => write!(f, "invalid timestamp '{}'", value),
+                ErrorKind::Invalid(errors) => {
+                    let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+                    write!(f, "{}", messages.join("; "))
+                }
+                ErrorKind::Csv(message) => write!(f, "{}", message),
+            }
+        }
+    }
+
+    impl std::error::Error for CsvError {}
+
+    /// Reads OHLC from CSV with a header row, validating each row.
+    ///
+    /// Every bad row is reported, so the error holds one entry per failing line.
+    pub fn read<R: io::Read>(reader: R, opts: &Opts) -> Result<Vec<OHLC>, Vec<CsvError>> {
+        let mut reader = ::csv::ReaderBuilder::new()
+            .delimiter(opts.delimiter)
+            .trim(::csv::Trim::All)
+            .from_reader(reader);
+
+        let headers = reader.headers().map_err(|e| vec![csv_error(1, e)])?.clone();
+
+        let index = |name: &str| headers.iter().position(|h| h == name);
+        let columns = &opts.columns;
+        let required = [
+            &columns.open,
+            &columns.high,
+            &columns.low,
+            &columns.close,
+            &columns.ts,
+        ];
+
+        let missing: Vec<CsvError> = required
+            .into_iter()
+            .chain(columns.volume.as_ref())
+            .filter(|name| index(name).is_none())
+            .map(|name| CsvError {
+                line: 1,
+                kind: ErrorKind::MissingColumn(name.to_string()),
+            })
+            .collect();
+        if !missing.is_empty() {
+            return Err(missing);
+        }
+
+        let [open, high, low, close, ts] = required.map(|name| index(name).unwrap_or_default());
+        let volume = columns.volume.as_deref().and_then(index);
+
+        let mut ohlcs = Vec::new();
+        let mut errors = Vec::new();
+
+        for (i, record) in reader.records().enumerate() {
+            // Fall back to counting rows when the reader cannot give a position.
+            let fallback = i as u64 + 2;
+
+            let record = match record {
+                Ok(record) => record,
+                Err(e) => {
+                    let line = e.position().map_or(fallback, |p| p.line());
+                    errors.push(csv_error(line, e));
+                    continue;
+                }
+            };
+            let line = record.position().map_or(fallback, |p| p.line());
+            let field = |i: usize| record.get(i).unwrap_or_default();
+
+            let number = |i: usize, column: &String| {
+                field(i).parse::<f64>().map_err(|_| CsvError {
+                    line,
+                    kind: ErrorKind::InvalidNumber {
+                        column: column.clone(),
+                        value: field(i).to_string(),
+                    },
+                })
+            };
+
+            let row = (|| {
+                let mut ohlc = OHLC::new(
+                    number(open, &columns.open)?,
+                    number(high, &columns.high)?,
+                    number(low, &columns.low)?,
+                    number(close, &columns.close)?,
+                    parse_timestamp(field(ts), opts.format, opts.unit).ok_or_else(|| CsvError {
+                        line,
+                        kind: ErrorKind::InvalidTimestamp(field(ts).to_string()),
+                    })?,
+                );
+
+                if let (Some(i), Some(column)) = (volume, &columns.volume) {
+                    if !field(i).is_empty() {
+                        ohlc = ohlc.with_volume(number(i, column)?);
+                    }
+                }
+
+                ohlc.validate().map_err(|e| CsvError {
+                    line,
+                    kind: ErrorKind::Invalid(e),
+                })?;
+
+                Ok(ohlc)
+            })();
+
+            match row {
+                Ok(ohlc) => ohlcs.push(ohlc),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(ohlcs)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Writes OHLC as CSV with a header row. An empty volume is written for OHLC without one.
+    pub fn write<W: io::Write>(writer: W, ohlcs: &[OHLC], opts: &Opts) -> Result<(), ::csv::Error> {
+        let mut writer = ::csv::WriterBuilder::new()
+            .delimiter(opts.delimiter)
+            .from_writer(writer);
+        let columns = &opts.columns;
+
+        let mut header = vec![
+            columns.ts.as_str(),
+            columns.open.as_str(),
+            columns.high.as_str(),
+            columns.low.as_str(),
+            columns.close.as_str(),
+        ];
+        header.extend(columns.volume.as_deref());
+        writer.write_record(&header)?;
+
+        for ohlc in ohlcs {
+            let mut record = vec![
+                format_timestamp(ohlc.ts, opts.format, opts.unit),
+                ohlc.open.to_string(),
+                ohlc.high.to_string(),
+                ohlc.low.to_string(),
+                ohlc.close.to_string(),
+            ];
+            if columns.volume.is_some() {
+                record.push(ohlc.vol.map(|v| v.to_string()).unwrap_or_default());
+            }
+            writer.write_record(&record)?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn csv_error(line: u64, error: ::csv::Error) -> CsvError {
+        CsvError {
+            line,
+            kind: ErrorKind::Csv(error.to_string()),
+        }
+    }
+
+    /// Parses a timestamp into the given unit.
+    fn parse_timestamp(value: &str, format: TimestampFormat, unit: Unit) -> Option<u64> {
+        match format {
+            TimestampFormat::Epoch(from) => {
+                let value: u128 = value.parse().ok()?;
+                let converted =
+                    value.checked_mul(unit.per_second() as u128)? / from.per_second() as u128;
+                u64::try_from(converted).ok()
+            }
+            TimestampFormat::Iso8601 => {
+                let (secs, nanos) = parse_iso8601(value)?;
+                let secs = u64::try_from(secs).ok()?;
+                let per_second = unit.per_second();
+                secs.checked_mul(per_second)?
+                    .checked_add(nanos / (NANOS_PER_SECOND / per_second))
+            }
+        }
+    }
+
+    fn format_timestamp(ts: u64, format: TimestampFormat, unit: Unit) -> String {
+        match format {
+            TimestampFormat::Epoch(to) => {
+                (ts as u128 * to.per_second() as u128 / unit.per_second() as u128).to_string()
+            }
+            TimestampFormat::Iso8601 => {
+                let per_second = unit.per_second();
+                let secs = (ts / per_second) as i64;
+                let fraction = ts % per_second;
+
+                let per_day = SECONDS_PER_DAY as i64;
+                let (y, m, d) = civil_from_days(secs.div_euclid(per_day));
+                let time = secs.rem_euclid(per_day);
+                let mut out = format!(
+                    "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+                    y,
+                    m,
+                    d,
+                    time / 3600,
+                    time % 3600 / 60,
+                    time % 60
+                );
+
+                if fraction > 0 {
+                    let digits = per_second.ilog10() as usize;
+                    out.push_str(&format!(".{:0width$}", fraction, width = digits));
+                }
+                out.push('Z');
+                out
+            }
+        }
+    }
+
+    /// Parses an ISO 8601 date or date time into seconds and nanoseconds since the Unix epoch.
+    ///
+    /// Accepts `YYYY-MM-DD`, optionally followed by `T` or a space, `HH:MM[:SS[.fraction]]` and
+    /// `Z` or an offset of the form `+HH:MM`, `+HHMM` or `+HH`. Without an offset the time is UTC.
+    fn parse_iso8601(value: &str) -> Option<(i64, u64)> {
+        let bytes = value.as_bytes();
+        if bytes.len() < 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+            return None;
+        }
+
+        let digits = |range: std::ops::Range<usize>| -> Option<i64> {
+            let part = value.get(range)?;
+            if part.bytes().all(|b| b.is_ascii_digit()) {
+                part.parse().ok()
+            } else {
+                None
+            }
+        };
+
+        let (y, m, d) = (digits(0..4)?, digits(5..7)?, digits(8..10)?);
+        let days = days_from_civil(y, m, d);
+        // Impossible dates such as 2021-02-31 don't survive the round trip.
+        if civil_from_days(days) != (y, m, d) {
+            return None;
+        }
+        let mut secs = days * SECONDS_PER_DAY as i64;
+        let mut nanos = 0;
+
+        let mut rest = &value[10..];
+        if rest.is_empty() {
+            return Some((secs, nanos));
+        }
+        if !(rest.starts_with('T') || rest.starts_with(' ')) {
+            return None;
+        }
+        rest = &rest[1..];
+
+        let time_end = rest.find(['Z', '+', '-']).unwrap_or(rest.len());
+        let (time, zone) = rest.split_at(time_end);
+
+        let (clock, fraction) = match time.split_once('.') {
+            Some((clock, fraction)) => (clock, Some(fraction)),
+            None => (time, None),
+        };
+
+        let mut parts = clock.split(':');
+        let hour: i64 = parts.next()?.parse().ok()?;
+        let minute: i64 = parts.next()?.parse().ok()?;
+        let second: i64 = parts.next().map_or(Some(0), |s| s.parse().ok())?;
+        if parts.next().is_some() || hour > 23 || minute > 59 || second > 60 {
+            return None;
+        }
+        secs += hour * 3600 + minute * 60 + second;
+
+        if let Some(fraction) = fraction {
+            if fraction.is_empty()
+                || fraction.len() > 9
+                || !fraction.bytes().all(|b| b.is_ascii_digit())
+            {
+                return None;
+            }
+            nanos = fraction.parse::<u64>().ok()? * 10u64.pow(9 - fraction.len() as u32);
+        }
+
+        match zone {
+            "" | "Z" => {}
+            _ => {
+                let sign = if zone.starts_with('-') { 1 } else { -1 };
+                let offset = zone[1..].replace(':', "");
+                if (offset.len() != 2 && offset.len() != 4)
+                    || !offset.bytes().all(|b| b.is_ascii_digit())
+                {
+                    return None;
+                }
+                let hours: i64 = offset[0..2].parse().ok()?;
+                let minutes: i64 = offset.get(2..4).map_or(Some(0), |m| m.parse().ok())?;
+                if hours > 23 || minutes > 59 {
+                    return None;
+                }
+                secs += sign * (hours * 3600 + minutes * 60);
+            }
+        }
+
+        Some((secs, nanos))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_read() {
+            let data = "\
+ts,open,high,low,close,volume
+1625097600,100,110,95,105,12345
+1625097660, 105 ,112,104,111,
+";
+            let result = read(data.as_bytes(), &Opts::default()).unwrap();
+            let expected = vec![
+                OHLC::new(100.0, 110.0, 95.0, 105.0, 1625097600).with_volume(12345.0),
+                OHLC::new(105.0, 112.0, 104.0, 111.0, 1625097660),
+            ];
+
+            assert_eq!(result, expected, "Rows should map to OHLC");
+        }
+
+        #[test]
+        fn test_read_columns_and_formats() {
+            struct TestCase {
+                name: &'static str,
+                data: &'static str,
+                format: TimestampFormat,
+                unit: Unit,
+                expected: u64,
+            }
+
+            let test_cases = vec![
+                TestCase {
+                    name: "millis to seconds",
+                    data: "Date;O;H;L;C\n1625097600000;1;2;0.5;1.5\n",
+                    format: TimestampFormat::Epoch(Unit::Millis),
+                    unit: Unit::Seconds,
+                    expected: 1625097600,
+                },
+                TestCase {
+                    name: "seconds to millis",
+                    data: "Date;O;H;L;C\n1625097600;1;2;0.5;1.5\n",
+                    format: TimestampFormat::Epoch(Unit::Seconds),
+                    unit: Unit::Millis,
+                    expected: 1625097600000,
+                },
+                TestCase {
+                    name: "iso date",
+                    data: "Date;O;H;L;C\n2021-07-01;1;2;0.5;1.5\n",
+                    format: TimestampFormat::Iso8601,
+                    unit: Unit::Seconds,
+                    expected: 1625097600,
+                },
+                TestCase {
+                    name: "iso fraction to millis",
+                    data: "Date;O;H;L;C\n2021-07-01T00:00:01.25Z;1;2;0.5;1.5\n",
+                    format: TimestampFormat::Iso8601,
+                    unit: Unit::Millis,
+                    expected: 1625097601250,
+                },
+                TestCase {
+                    name: "iso offset",
+                    data: "Date;O;H;L;C\n2021-07-01 10:00+10:00;1;2;0.5;1.5\n",
+                    format: TimestampFormat::Iso8601,
+                    unit: Unit::Seconds,
+                    expected: 1625097600,
+                },
+            ];
+
+            for case in test_cases {
+                let opts = Opts {
+                    columns: Columns {
+                        open: "O".to_string(),
+                        high: "H".to_string(),
+                        low: "L".to_string(),
+                        close: "C".to_string(),
+                        volume: None,
+                        ts: "Date".to_string(),
+                    },
+                    format: case.format,
+                    unit: case.unit,
+                    delimiter: b';',
+                };
+
+                let result = read(case.data.as_bytes(), &opts).unwrap();
+
+                assert_eq!(
+                    result,
+                    vec![OHLC::new(1.0, 2.0, 0.5, 1.5, case.expected)],
+                    "Test case '{}' failed",
+                    case.name
+                );
+            }
+        }
+
+        #[test]
+        fn test_read_errors() {
+            let data = "\
+ts,open,high,low,close,volume
+1625097600,100,110,95,105,1
+1625097660,abc,110,95,105,1
+yesterday,100,110,95,105,1
+1625097780,100,90,95,105,1
+";
+            let errors = read(data.as_bytes(), &Opts::default()).unwrap_err();
+
+            assert_eq!(
+                errors,
+                vec![
+                    CsvError {
+                        line: 3,
+                        kind: ErrorKind::InvalidNumber {
+                            column: "open".to_string(),
+                            value: "abc".to_string()
+                        }
+                    },
+                    CsvError {
+                        line: 4,
+                        kind: ErrorKind::InvalidTimestamp("yesterday".to_string())
+                    },
+                    CsvError {
+                        line: 5,
+                        kind: ErrorKind::Invalid(vec![OhlcError::HighBelowLow])
+                    },
+                ]
+            );
+            assert_eq!(
+                errors[2].to_string(),
+                "line 5: High price must be greater than or equal to low price"
+            );
+        }
+
+        #[test]
+        fn test_read_missing_column() {
+            let data = "ts,open,high,low\n1625097600,1,2,0.5\n";
+            let errors = read(data.as_bytes(), &Opts::default()).unwrap_err();
+
+            assert_eq!(
+                errors,
+                vec![
+                    CsvError {
+                        line: 1,
+                        kind: ErrorKind::MissingColumn("close".to_string())
+                    },
+                    CsvError {
+                        line: 1,
+                        kind: ErrorKind::MissingColumn("volume".to_string())
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn test_write_round_trip() {
+            let ohlcs = vec![
+                OHLC::new(100.0, 110.0, 95.0, 105.5, 1625097600250).with_volume(12345.0),
+                OHLC::new(105.5, 112.0, 104.0, 111.0, 1625097660000),
+            ];
+            let opts = Opts {
+                format: TimestampFormat::Iso8601,
+                unit: Unit::Millis,
+                ..Opts::default()
+            };
+
+            let mut out = Vec::new();
+            write(&mut out, &ohlcs, &opts).unwrap();
+            let text = String::from_utf8(out).unwrap();
+
+            assert_eq!(
+                text,
+                "\
+ts,open,high,low,close,volume
+2021-07-01T00:00:00.250Z,100,110,95,105.5,12345
+2021-07-01T00:01:00Z,105.5,112,104,111,
+"
+            );
+            assert_eq!(read(text.as_bytes(), &opts).unwrap(), ohlcs);
+        }
+
+        #[test]
+        fn test_parse_iso8601_invalid() {
+            for value in [
+                "2021-13-01",
+                "2021-07-01T25:00",
+                "2021/07/01",
+                "2021-07-01T00:00+5",
+                "2021-02-29",
+                "2021-02-31",
+                "2021-04-31",
+                "2021-07-01T00:00+24:00",
+                "2021-07-01T00:00+05:60",
+                "2021-07-01T00:00++5:00",
+                "",
+            ] {
+                assert_eq!(parse_iso8601(value), None, "'{}' should not parse", value);
+            }
+        }
+
+        #[test]
+        fn test_parse_iso8601_calendar() {
+            assert_eq!(parse_iso8601("2020-02-29"), Some((1582934400, 0)));
+            assert_eq!(parse_iso8601("2000-02-29"), Some((951782400, 0)));
+            assert_eq!(parse_iso8601("1900-02-29"), None);
+            assert_eq!(
+                parse_iso8601("2021-07-01T00:00-23:59"),
+                Some((1625097600 + 23 * 3600 + 59 * 60, 0))
+            );
+        }
+
+        #[test]
+        fn test_parse_timestamp_overflow() {
+            let huge = "300000000000000000000000000000000000";
+
+            assert_eq!(
+                parse_timestamp(huge, TimestampFormat::Epoch(Unit::Seconds), Unit::Nanos),
+                None
+            );
+            assert_eq!(
+                parse_timestamp(
+                    "18446744073709551616",
+                    TimestampFormat::Epoch(Unit::Seconds),
+                    Unit::Seconds
+                ),
+                None
+            );
+
+            let opts = Opts {
+                format: TimestampFormat::Epoch(Unit::Seconds),
+                unit: Unit::Nanos,
+                ..Opts::default()
+            };
+            let data = format!("ts,open,high,low,close,volume\n{},1,2,0.5,1.5,10\n", huge);
+
+            assert_eq!(
+                read(data.as_bytes(), &opts),
+                Err(vec![CsvError {
+                    line: 2,
+                    kind: ErrorKind::InvalidTimestamp(huge.to_string()),
+                }])
+            );
+        }
+    }
+}
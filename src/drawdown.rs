@@ -0,0 +1,225 @@
+pub mod drawdown {
+    use crate::ohlc::ohlc::OHLC;
+    use std::cmp::Ordering;
+
+    /// A fall from a peak in an equity curve, lasting until equity regains the peak.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct Drawdown {
+        /// The fall from the peak to the trough as a positive fraction of the peak.
+        pub depth: f64,
+        pub peak_ts: u64,
+        pub trough_ts: u64,
+        /// When equity first regained the peak, or None if it has not.
+        pub recovery_ts: Option<u64>,
+        /// The time from the peak to the recovery, or to the end of the curve if not recovered.
+        pub duration: u64,
+    }
+
+    impl Drawdown {
+        /// Returns true if equity has not yet regained the peak.
+        pub fn is_ongoing(&self) -> bool {
+            self.recovery_ts.is_none()
+        }
+    }
+
+    /// Every drawdown in an equity curve of `(ts, equity)`, in time order.
+    ///
+    /// A drawdown starts when equity falls below the running peak. The last drawdown is ongoing
+    /// if equity ends below its peak.
+    ///
+    /// The curve must be in timestamp order. Durations that would run backwards are zero.
+    pub fn drawdowns(equity: &[(u64, f64)]) -> Vec<Drawdown> {
+        let (Some(&first), Some(&(last_ts, _))) = (equity.first(), equity.last()) else {
+            return Vec::new();
+        };
+
+        let mut result = Vec::new();
+        let mut peak = first;
+        let mut current: Option<Drawdown> = None;
+
+        for &(ts, value) in equity {
+            if value >= peak.1 {
+                if let Some(mut drawdown) = current.take() {
+                    drawdown.recovery_ts = Some(ts);
+                    drawdown.duration = ts.saturating_sub(drawdown.peak_ts);
+                    result.push(drawdown);
+                }
+                peak = (ts, value);
+                continue;
+            }
+
+            let depth = 1.0 - value / peak.1;
+            match current.as_mut() {
+                Some(drawdown) => {
+                    if depth > drawdown.depth {
+                        drawdown.depth = depth;
+                        drawdown.trough_ts = ts;
+                    }
+                }
+                None => {
+                    current = Some(Drawdown {
+                        depth,
+                        peak_ts: peak.0,
+                        trough_ts: ts,
+                        recovery_ts: None,
+                        duration: last_ts.saturating_sub(peak.0),
+                    })
+                }
+            }
+        }
+
+        result.extend(current);
+        result
+    }
+
+    /// Every drawdown in the closes of a slice of OHLC, in time order.
+    pub fn close_drawdowns(ohlcs: &[OHLC]) -> Vec<Drawdown> {
+        drawdowns(&close_equity(ohlcs))
+    }
+
+    /// Sorts drawdowns deepest first, keeping the time order of equal depths.
+    pub fn sort_by_depth(drawdowns: &mut [Drawdown]) {
+        drawdowns.sort_by(|a, b| b.depth.partial_cmp(&a.depth).unwrap_or(Ordering::Equal));
+    }
+
+    /// The underwater curve, the change from the running peak at each point.
+    ///
+    /// Values are zero at a new peak and negative below it, so -0.1 is 10% below the peak.
+    pub fn underwater(equity: &[(u64, f64)]) -> Vec<(u64, f64)> {
+        let mut peak = f64::MIN;
+
+        equity
+            .iter()
+            .map(|&(ts, value)| {
+                peak = peak.max(value);
+                (ts, value / peak - 1.0)
+            })
+            .collect()
+    }
+
+    /// The underwater curve of the closes of a slice of OHLC.
+    pub fn close_underwater(ohlcs: &[OHLC]) -> Vec<(u64, f64)> {
+        underwater(&close_equity(ohlcs))
+    }
+
+    fn close_equity(ohlcs: &[OHLC]) -> Vec<(u64, f64)> {
+        ohlcs.iter().map(|ohlc| (ohlc.ts, ohlc.close)).collect()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const EPSILON: f64 = 1e-12;
+
+        fn summary(drawdowns: &[Drawdown]) -> Vec<(f64, u64, u64, Option<u64>, u64)> {
+            drawdowns
+                .iter()
+                .map(|dd| {
+                    (
+                        (dd.depth * 1e9).round() / 1e9,
+                        dd.peak_ts,
+                        dd.trough_ts,
+                        dd.recovery_ts,
+                        dd.duration,
+                    )
+                })
+                .collect()
+        }
+
+        #[test]
+        fn test_drawdowns() {
+            let test_cases = vec![
+                ("empty", vec![], vec![]),
+                ("rising", vec![(1, 100.0), (2, 100.0), (3, 101.0)], vec![]),
+                (
+                    "recovered and ongoing",
+                    vec![
+                        (1, 100.0),
+                        (2, 90.0),
+                        (3, 95.0),
+                        (4, 85.0),
+                        (5, 100.0),
+                        (6, 120.0),
+                        (7, 108.0),
+                        (8, 110.0),
+                    ],
+                    vec![(0.15, 1, 4, Some(5), 4), (0.1, 6, 7, None, 2)],
+                ),
+                (
+                    "back to back",
+                    vec![(1, 100.0), (2, 80.0), (3, 100.0), (4, 95.0), (5, 101.0)],
+                    vec![(0.2, 1, 2, Some(3), 2), (0.05, 3, 4, Some(5), 2)],
+                ),
+            ];
+
+            for (name, equity, expected) in test_cases {
+                let result = drawdowns(&equity);
+
+                assert_eq!(summary(&result), expected, "Test case '{}' failed", name);
+            }
+        }
+
+        #[test]
+        fn test_drawdowns_unsorted() {
+            // Unsorted input has no meaningful drawdowns, but must not underflow a duration.
+            let equity = vec![(5, 100.0), (3, 90.0), (1, 100.0), (4, 95.0), (2, 96.0)];
+
+            let _ = drawdowns(&equity);
+        }
+
+        #[test]
+        fn test_sort_by_depth() {
+            let equity = vec![
+                (1, 100.0),
+                (2, 95.0),
+                (3, 100.0),
+                (4, 80.0),
+                (5, 100.0),
+                (6, 95.0),
+            ];
+            let mut result = drawdowns(&equity);
+
+            sort_by_depth(&mut result);
+
+            assert_eq!(
+                summary(&result),
+                vec![
+                    (0.2, 3, 4, Some(5), 2),
+                    (0.05, 1, 2, Some(3), 2),
+                    (0.05, 5, 6, None, 1),
+                ]
+            );
+            assert!(result[2].is_ongoing());
+        }
+
+        #[test]
+        fn test_underwater() {
+            let equity = vec![(1, 100.0), (2, 90.0), (3, 110.0), (4, 99.0)];
+            let expected = [(1, 0.0), (2, -0.1), (3, 0.0), (4, -0.1)];
+
+            let result = underwater(&equity);
+
+            assert_eq!(result.len(), expected.len());
+            for ((ts, value), (expected_ts, expected_value)) in result.iter().zip(expected) {
+                assert_eq!(*ts, expected_ts);
+                assert!((value - expected_value).abs() < EPSILON, "at {}", ts);
+            }
+        }
+
+        #[test]
+        fn test_closes() {
+            let ohlcs = vec![
+                OHLC::new(100.0, 101.0, 99.0, 100.0, 1000),
+                OHLC::new(100.0, 100.0, 70.0, 90.0, 2000),
+                OHLC::new(90.0, 101.0, 90.0, 100.0, 3000),
+            ];
+
+            assert_eq!(
+                summary(&close_drawdowns(&ohlcs)),
+                vec![(0.1, 1000, 2000, Some(3000), 2000)]
+            );
+            assert_eq!(close_underwater(&ohlcs)[2], (3000, 0.0));
+        }
+    }
+}
@@ -0,0 +1,295 @@
+pub mod fees {
+    use crate::models::models::{Fill, Instrument, Liquidity};
+
+    /// Calculates the fee charged for a fill.
+    pub trait FeeModel {
+        /// Returns the fee for a fill, in the instrument's currency.
+        fn fee(&mut self, fill: &Fill, instrument: &Instrument) -> f64;
+
+        /// Discard any state, such as volume traded, as if no fills had been seen.
+        fn reset(&mut self) {}
+    }
+
+    /// Returns the fill with its fee set by a model.
+    pub fn charge<F: FeeModel + ?Sized>(
+        model: &mut F,
+        fill: Fill,
+        instrument: &Instrument,
+    ) -> Fill {
+        let fee = model.fee(&fill, instrument);
+        fill.with_fee(fee)
+    }
+
+    /// Charges nothing.
+    #[derive(Debug, PartialEq, Clone, Copy, Default)]
+    pub struct NoFee;
+
+    impl FeeModel for NoFee {
+        fn fee(&mut self, _fill: &Fill, _instrument: &Instrument) -> f64 {
+            0.0
+        }
+    }
+
+    /// A fee for each share traded.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct PerShare(pub f64);
+
+    impl FeeModel for PerShare {
+        fn fee(&mut self, fill: &Fill, _instrument: &Instrument) -> f64 {
+            fill.quantity * self.0
+        }
+    }
+
+    /// A fee for each contract traded, regardless of the contract multiplier.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct PerContract(pub f64);
+
+    impl FeeModel for PerContract {
+        fn fee(&mut self, fill: &Fill, instrument: &Instrument) -> f64 {
+            PerShare(self.0).fee(fill, instrument)
+        }
+    }
+
+    /// A fraction of the notional value traded, including the contract multiplier, so 0.001 is
+    /// 10 basis points.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct Percentage(pub f64);
+
+    impl FeeModel for Percentage {
+        fn fee(&mut self, fill: &Fill, instrument: &Instrument) -> f64 {
+            notional(fill, instrument) * self.0
+        }
+    }
+
+    /// Fractions of notional value that fall as more is traded.
+    ///
+    /// Each tier is a `(volume, rate)` pair, with the rate applying once the notional value
+    /// traded before a fill reaches the volume. Fills before the first tier's volume use its rate.
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct Tiered {
+        tiers: Vec<(f64, f64)>,
+        traded: f64,
+    }
+
+    impl Tiered {
+        /// Return a new Tiered model, with tiers in any order. No tiers charges nothing.
+        pub fn new(mut tiers: Vec<(f64, f64)>) -> Self {
+            tiers.sort_by(|a, b| a.0.total_cmp(&b.0));
+            Tiered { tiers, traded: 0.0 }
+        }
+
+        /// The notional value traded so far.
+        pub fn traded(&self) -> f64 {
+            self.traded
+        }
+    }
+
+    impl FeeModel for Tiered {
+        fn fee(&mut self, fill: &Fill, instrument: &Instrument) -> f64 {
+            let rate = self
+                .tiers
+                .iter()
+                .rev()
+                .find(|(volume, _)| self.traded >= *volume)
+                .or(self.tiers.first())
+                .map_or(0.0, |(_, rate)| *rate);
+
+            let notional = notional(fill, instrument);
+            self.traded += notional;
+            notional * rate
+        }
+
+        fn reset(&mut self) {
+            self.traded = 0.0;
+        }
+    }
+
+    /// Fractions of notional value for fills that add and remove liquidity. A negative maker
+    /// rate is a rebate.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct MakerTaker {
+        pub maker: f64,
+        pub taker: f64,
+    }
+
+    impl FeeModel for MakerTaker {
+        fn fee(&mut self, fill: &Fill, instrument: &Instrument) -> f64 {
+            let rate = match fill.liquidity {
+                Liquidity::Maker => self.maker,
+                Liquidity::Taker => self.taker,
+            };
+            notional(fill, instrument) * rate
+        }
+    }
+
+    /// Limits the fee of another model to a minimum and maximum per fill.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct Capped<F> {
+        model: F,
+        min: Option<f64>,
+        max: Option<f64>,
+    }
+
+    impl<F: FeeModel> Capped<F> {
+        /// Return a new, uncapped model.
+        pub fn new(model: F) -> Self {
+            Capped {
+                model,
+                min: None,
+                max: None,
+            }
+        }
+
+        /// Specify the minimum fee per fill.
+        pub fn with_min(mut self, min: f64) -> Self {
+            self.min = Some(min);
+            self
+        }
+
+        /// Specify the maximum fee per fill.
+        pub fn with_max(mut self, max: f64) -> Self {
+            self.max = Some(max);
+            self
+        }
+    }
+
+    impl<F: FeeModel> FeeModel for Capped<F> {
+        fn fee(&mut self, fill: &Fill, instrument: &Instrument) -> f64 {
+            let mut fee = self.model.fee(fill, instrument);
+            if let Some(max) = self.max {
+                fee = fee.min(max);
+            }
+            if let Some(min) = self.min {
+                fee = fee.max(min);
+            }
+            fee
+        }
+
+        fn reset(&mut self) {
+            self.model.reset();
+        }
+    }
+
+    fn notional(fill: &Fill, instrument: &Instrument) -> f64 {
+        fill.notional() * instrument.multiplier
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::models::models::Side;
+
+        const EPSILON: f64 = 1e-9;
+
+        type TestCase<'a> = (&'a str, Box<dyn FeeModel>, &'a Instrument, Fill, f64);
+
+        fn fill(price: f64, quantity: f64) -> Fill {
+            Fill::new(1, "ES", Side::Buy, price, quantity, 1000)
+        }
+
+        #[test]
+        fn test_fees() {
+            let stock = Instrument::new("AAPL", "USD");
+            let future = Instrument::new("ES", "USD").with_multiplier(50.0);
+
+            let test_cases: Vec<TestCase> = vec![
+                ("none", Box::new(NoFee), &stock, fill(100.0, 10.0), 0.0),
+                (
+                    "per share",
+                    Box::new(PerShare(0.005)),
+                    &stock,
+                    fill(100.0, 200.0),
+                    1.0,
+                ),
+                (
+                    "per contract",
+                    Box::new(PerContract(2.25)),
+                    &future,
+                    fill(4000.0, 4.0),
+                    9.0,
+                ),
+                (
+                    "percentage",
+                    Box::new(Percentage(0.0001)),
+                    &future,
+                    fill(4000.0, 2.0),
+                    40.0,
+                ),
+                (
+                    "maker rebate",
+                    Box::new(MakerTaker {
+                        maker: -0.0001,
+                        taker: 0.0004,
+                    }),
+                    &stock,
+                    fill(100.0, 100.0).with_liquidity(Liquidity::Maker),
+                    -1.0,
+                ),
+                (
+                    "taker",
+                    Box::new(MakerTaker {
+                        maker: -0.0001,
+                        taker: 0.0004,
+                    }),
+                    &stock,
+                    fill(100.0, 100.0),
+                    4.0,
+                ),
+                (
+                    "minimum",
+                    Box::new(Capped::new(PerShare(0.005)).with_min(1.0).with_max(5.0)),
+                    &stock,
+                    fill(100.0, 10.0),
+                    1.0,
+                ),
+                (
+                    "maximum",
+                    Box::new(Capped::new(PerShare(0.005)).with_min(1.0).with_max(5.0)),
+                    &stock,
+                    fill(100.0, 5000.0),
+                    5.0,
+                ),
+            ];
+
+            for (name, mut model, instrument, fill, expected) in test_cases {
+                let result = model.fee(&fill, instrument);
+
+                assert!(
+                    (result - expected).abs() < EPSILON,
+                    "Test case '{}' failed: expected {}, got {}",
+                    name,
+                    expected,
+                    result
+                );
+            }
+        }
+
+        #[test]
+        fn test_tiered() {
+            let stock = Instrument::new("AAPL", "USD");
+            let mut model = Tiered::new(vec![(10_000.0, 0.0005), (0.0, 0.001)]);
+
+            let fees: Vec<f64> = [fill(100.0, 60.0), fill(100.0, 60.0), fill(100.0, 10.0)]
+                .iter()
+                .map(|fill| model.fee(fill, &stock))
+                .collect();
+
+            assert_eq!(fees, vec![6.0, 6.0, 0.5]);
+            assert_eq!(model.traded(), 13_000.0);
+
+            model.reset();
+            assert_eq!(model.fee(&fill(100.0, 10.0), &stock), 1.0);
+            assert_eq!(Tiered::new(vec![]).fee(&fill(100.0, 10.0), &stock), 0.0);
+        }
+
+        #[test]
+        fn test_charge() {
+            let stock = Instrument::new("AAPL", "USD");
+
+            let result = charge(&mut PerShare(0.01), fill(100.0, 300.0), &stock);
+
+            assert_eq!(result.fee, 3.0);
+            assert_eq!(result.quantity, 300.0);
+        }
+    }
+}
@@ -0,0 +1,437 @@
+pub mod gaps {
+    use crate::ohlc::ohlc::OHLC;
+    use crate::timeframe::timeframe::Timeframe;
+    use crate::timestamp::timestamp::Unit;
+    use std::fmt;
+
+    /// A run of missing OHLC.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub struct Gap {
+        /// The index of the first OHLC after the gap.
+        pub index: usize,
+        /// The start of the first missing interval.
+        pub from: u64,
+        /// The start of the last missing interval.
+        pub to: u64,
+        /// The number of missing intervals.
+        pub missing: usize,
+    }
+
+    /// A data quality report for a slice of OHLC.
+    #[derive(Debug, PartialEq, Eq, Clone, Default)]
+    pub struct Report {
+        /// The number of OHLC checked.
+        pub observed: usize,
+        pub gaps: Vec<Gap>,
+        /// Indices of OHLC with the same timestamp as the one before.
+        pub duplicates: Vec<usize>,
+        /// Indices of OHLC earlier than the one before.
+        pub unsorted: Vec<usize>,
+        /// Indices of OHLC not starting on an interval boundary.
+        pub misaligned: Vec<usize>,
+    }
+
+    impl Report {
+        /// Returns true if no problems were found.
+        pub fn is_clean(&self) -> bool {
+            self.gaps.is_empty()
+                && self.duplicates.is_empty()
+                && self.unsorted.is_empty()
+                && self.misaligned.is_empty()
+        }
+
+        /// The total number of missing intervals.
+        pub fn missing(&self) -> usize {
+            self.gaps
+                .iter()
+                .fold(0, |total: usize, gap| total.saturating_add(gap.missing))
+        }
+
+        /// The fraction of expected intervals that have an OHLC.
+        ///
+        /// Will return one if there are no OHLC.
+        pub fn coverage(&self) -> f64 {
+            let expected = self.observed.saturating_add(self.missing());
+            if expected == 0 {
+                1.0
+            } else {
+                self.observed as f64 / expected as f64
+            }
+        }
+    }
+
+    /// How missing intervals are filled.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum Policy {
+        /// Flat OHLC at the previous close with zero volume.
+        Forward,
+        /// OHLC stepping in a straight line from the previous close to the next open, with zero
+        /// volume.
+        Linear,
+        /// Leave a `Slot::Missing` marker.
+        Mark,
+    }
+
+    /// An interval in a filled series.
+    #[derive(Debug, PartialEq, Clone)]
+    pub enum Slot {
+        Observed(OHLC),
+        Filled(OHLC),
+        /// A missing interval, with its start.
+        Missing(u64),
+    }
+
+    impl Slot {
+        /// Returns the OHLC, if the slot has one.
+        pub fn ohlc(&self) -> Option<&OHLC> {
+            match self {
+                Slot::Observed(ohlc) | Slot::Filled(ohlc) => Some(ohlc),
+                Slot::Missing(_) => None,
+            }
+        }
+
+        /// Returns the start of the interval.
+        pub fn ts(&self) -> u64 {
+            match self {
+                Slot::Observed(ohlc) | Slot::Filled(ohlc) => ohlc.ts,
+                Slot::Missing(ts) => *ts,
+            }
+        }
+    }
+
+    /// The most intervals `fill` will add for a single gap.
+    pub const MAX_FILL: usize = 1_000_000;
+
+    /// Errors returned when checking or filling gaps.
+    #[derive(Debug, PartialEq, Eq, Clone)]
+    pub enum GapError {
+        /// The timeframe is measured in ticks or volume, so has no expected intervals.
+        NotTimeBased,
+        /// The gap before the OHLC at `index` has more than `MAX_FILL` missing intervals.
+        TooManyMissing { index: usize, missing: usize },
+        /// The OHLC at `index` is earlier than the one before it.
+        Unsorted { index: usize },
+        /// The OHLC at `index` has the same timestamp as the one before it.
+        DuplicateTimestamp { index: usize, ts: u64 },
+    }
+
+    impl fmt::Display for GapError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                GapError::NotTimeBased => write!(f, "Timeframe must be time based"),
+                GapError::TooManyMissing { index, missing } => write!(
+                    f,
+                    "OHLC at index {} follows {} missing intervals, more than can be filled",
+                    index, missing
+                ),
+                GapError::Unsorted { index } => {
+                    write!(f, "OHLC at index {} is out of timestamp order", index)
+                }
+                GapError::DuplicateTimestamp { index, ts } => {
+                    write!(f, "OHLC at index {} has duplicate timestamp {}", index, ts)
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for GapError {}
+
+    /// Checks a slice of OHLC against the intervals of a timeframe.
+    ///
+    /// Each OHLC is compared with the last in-order OHLC before it, so a single out of order
+    /// OHLC is reported once rather than also opening a gap.
+    pub fn check(ohlcs: &[OHLC], timeframe: Timeframe, unit: Unit) -> Result<Report, GapError> {
+        if !timeframe.is_time_based() {
+            return Err(GapError::NotTimeBased);
+        }
+
+        let mut report = Report {
+            observed: ohlcs.len(),
+            ..Report::default()
+        };
+        let mut prev: Option<u64> = None;
+
+        for (index, ohlc) in ohlcs.iter().enumerate() {
+            if !timeframe.is_aligned(ohlc.ts, unit) {
+                report.misaligned.push(index);
+            }
+
+            match prev {
+                Some(last) if ohlc.ts == last => report.duplicates.push(index),
+                Some(last) if ohlc.ts < last => report.unsorted.push(index),
+                Some(last) => {
+                    if let Some(gap) = gap(last, ohlc.ts, index, timeframe, unit) {
+                        report.gaps.push(gap);
+                    }
+                    prev = Some(ohlc.ts);
+                }
+                None => prev = Some(ohlc.ts),
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Returns a slot for every interval from the first OHLC to the last, filling missing
+    /// intervals with the policy.
+    ///
+    /// The OHLC must have strictly increasing timestamps, and no gap may have more than
+    /// `MAX_FILL` missing intervals.
+    pub fn fill(
+        ohlcs: &[OHLC],
+        timeframe: Timeframe,
+        unit: Unit,
+        policy: Policy,
+    ) -> Result<Vec<Slot>, GapError> {
+        if !timeframe.is_time_based() {
+            return Err(GapError::NotTimeBased);
+        }
+
+        let mut slots = Vec::with_capacity(ohlcs.len());
+
+        for (index, ohlc) in ohlcs.iter().enumerate() {
+            if let Some(prev) = index.checked_sub(1).map(|i| &ohlcs[i]) {
+                if ohlc.ts == prev.ts {
+                    return Err(GapError::DuplicateTimestamp { index, ts: ohlc.ts });
+                }
+                if ohlc.ts < prev.ts {
+                    return Err(GapError::Unsorted { index });
+                }
+
+                let Some(gap) = gap(prev.ts, ohlc.ts, index, timeframe, unit) else {
+                    slots.push(Slot::Observed(ohlc.clone()));
+                    continue;
+                };
+                if gap.missing > MAX_FILL {
+                    return Err(GapError::TooManyMissing {
+                        index,
+                        missing: gap.missing,
+                    });
+                }
+                let step = (ohlc.open - prev.close) / (gap.missing + 1) as f64;
+                let starts = std::iter::successors(Some(gap.from), |&start| {
+                    timeframe.next_start(start, unit)
+                });
+
+                slots.reserve(gap.missing);
+                for (i, ts) in starts.take(gap.missing).enumerate() {
+                    let slot = match policy {
+                        Policy::Forward => Slot::Filled(flat(prev.close, ts)),
+                        Policy::Linear => {
+                            let open = prev.close + step * i as f64;
+                            let close = prev.close + step * (i + 1) as f64;
+                            Slot::Filled(
+                                OHLC::new(open, open.max(close), open.min(close), close, ts)
+                                    .with_volume(0.0),
+                            )
+                        }
+                        Policy::Mark => Slot::Missing(ts),
+                    };
+                    slots.push(slot);
+                }
+            }
+
+            slots.push(Slot::Observed(ohlc.clone()));
+        }
+
+        Ok(slots)
+    }
+
+    fn flat(price: f64, ts: u64) -> OHLC {
+        OHLC::new(price, price, price, price, ts).with_volume(0.0)
+    }
+
+    /// The gap between two in-order timestamps, if any intervals are missing.
+    ///
+    /// The gap is calculated from its bounds, so a large one costs no more than a small one.
+    fn gap(prev: u64, ts: u64, index: usize, timeframe: Timeframe, unit: Unit) -> Option<Gap> {
+        let from = timeframe.next_start(prev, unit)?;
+        let end = timeframe.bucket_start(ts, unit)?;
+        if from >= end {
+            return None;
+        }
+
+        let missing = timeframe.buckets_between(from, end, unit)?;
+        Some(Gap {
+            index,
+            from,
+            to: timeframe.bucket_start(end - 1, unit)?,
+            missing: usize::try_from(missing).unwrap_or(usize::MAX),
+        })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn bar(open: f64, close: f64, ts: u64) -> OHLC {
+            OHLC::new(open, open.max(close), open.min(close), close, ts).with_volume(100.0)
+        }
+
+        #[test]
+        fn test_check() {
+            let ohlcs = vec![
+                bar(1.0, 2.0, 60),
+                bar(2.0, 3.0, 120),
+                bar(3.0, 4.0, 300),
+                bar(4.0, 5.0, 300),
+                bar(5.0, 6.0, 240),
+                bar(6.0, 7.0, 390),
+                bar(7.0, 8.0, 480),
+            ];
+
+            let report = check(&ohlcs, Timeframe::Minutes(1), Unit::Seconds).unwrap();
+
+            assert_eq!(
+                report,
+                Report {
+                    observed: 7,
+                    gaps: vec![
+                        Gap {
+                            index: 2,
+                            from: 180,
+                            to: 240,
+                            missing: 2
+                        },
+                        Gap {
+                            index: 6,
+                            from: 420,
+                            to: 420,
+                            missing: 1
+                        },
+                    ],
+                    duplicates: vec![3],
+                    unsorted: vec![4],
+                    misaligned: vec![5],
+                }
+            );
+            assert!(!report.is_clean());
+            assert_eq!(report.missing(), 3);
+            assert_eq!(report.coverage(), 0.7);
+        }
+
+        #[test]
+        fn test_check_clean() {
+            // Month starts for 2021-01 to 2021-03, which differ in length.
+            let ohlcs = vec![
+                bar(1.0, 2.0, 1609459200),
+                bar(2.0, 3.0, 1612137600),
+                bar(3.0, 4.0, 1614556800),
+            ];
+
+            let report = check(&ohlcs, Timeframe::Months(1), Unit::Seconds).unwrap();
+
+            assert!(report.is_clean());
+            assert_eq!(report.coverage(), 1.0);
+            assert_eq!(
+                check(&ohlcs, Timeframe::Ticks(10), Unit::Seconds),
+                Err(GapError::NotTimeBased)
+            );
+        }
+
+        #[test]
+        fn test_fill() {
+            let ohlcs = vec![bar(1.0, 2.0, 60), bar(5.0, 6.0, 240)];
+
+            let test_cases = vec![
+                (
+                    "forward",
+                    Policy::Forward,
+                    vec![Slot::Filled(flat(2.0, 120)), Slot::Filled(flat(2.0, 180))],
+                ),
+                (
+                    "linear",
+                    Policy::Linear,
+                    vec![
+                        Slot::Filled(OHLC::new(2.0, 3.0, 2.0, 3.0, 120).with_volume(0.0)),
+                        Slot::Filled(OHLC::new(3.0, 4.0, 3.0, 4.0, 180).with_volume(0.0)),
+                    ],
+                ),
+                (
+                    "mark",
+                    Policy::Mark,
+                    vec![Slot::Missing(120), Slot::Missing(180)],
+                ),
+            ];
+
+            for (name, policy, filled) in test_cases {
+                let result = fill(&ohlcs, Timeframe::Minutes(1), Unit::Seconds, policy).unwrap();
+
+                let mut expected = vec![Slot::Observed(ohlcs[0].clone())];
+                expected.extend(filled);
+                expected.push(Slot::Observed(ohlcs[1].clone()));
+
+                assert_eq!(result, expected, "Test case '{}' failed", name);
+            }
+        }
+
+        #[test]
+        fn test_huge_gap() {
+            // A millisecond timestamp in a feed of seconds.
+            let ohlcs = vec![bar(1.0, 2.0, 60), bar(2.0, 3.0, 1_625_097_600_000)];
+
+            let report = check(&ohlcs, Timeframe::Seconds(1), Unit::Seconds).unwrap();
+
+            assert_eq!(
+                report.gaps,
+                vec![Gap {
+                    index: 1,
+                    from: 61,
+                    to: 1_625_097_599_999,
+                    missing: 1_625_097_599_939,
+                }]
+            );
+            assert_eq!(
+                fill(&ohlcs, Timeframe::Seconds(1), Unit::Seconds, Policy::Mark),
+                Err(GapError::TooManyMissing {
+                    index: 1,
+                    missing: 1_625_097_599_939
+                })
+            );
+
+            // Months vary in length, from 1970-01 to 2021-07.
+            let ohlcs = vec![bar(1.0, 2.0, 0), bar(2.0, 3.0, 1625097600)];
+            let report = check(&ohlcs, Timeframe::Months(1), Unit::Seconds).unwrap();
+
+            assert_eq!(
+                report.gaps,
+                vec![Gap {
+                    index: 1,
+                    from: 2678400,
+                    to: 1622505600,
+                    missing: 617,
+                }]
+            );
+        }
+
+        #[test]
+        fn test_fill_errors() {
+            let test_cases = vec![
+                (
+                    "unsorted",
+                    vec![bar(1.0, 2.0, 120), bar(2.0, 3.0, 60)],
+                    Timeframe::Minutes(1),
+                    GapError::Unsorted { index: 1 },
+                ),
+                (
+                    "duplicate",
+                    vec![bar(1.0, 2.0, 60), bar(2.0, 3.0, 60)],
+                    Timeframe::Minutes(1),
+                    GapError::DuplicateTimestamp { index: 1, ts: 60 },
+                ),
+                (
+                    "volume",
+                    vec![bar(1.0, 2.0, 60)],
+                    Timeframe::Volume(1000.0),
+                    GapError::NotTimeBased,
+                ),
+            ];
+
+            for (name, ohlcs, timeframe, expected) in test_cases {
+                let result = fill(&ohlcs, timeframe, Unit::Seconds, Policy::Mark);
+
+                assert_eq!(result, Err(expected), "Test case '{}' failed", name);
+            }
+        }
+    }
+}
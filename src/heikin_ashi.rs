@@ -0,0 +1,104 @@
+pub mod heikin_ashi {
+    use crate::ohlc::ohlc::OHLC;
+
+    /// Converts a slice of OHLC to Heikin-Ashi candles, preserving `ts` and `vol`.
+    ///
+    /// The first candle opens at the midpoint of its body. Each later candle opens at the midpoint
+    /// of the previous Heikin-Ashi body and closes at the average of its four prices.
+    pub fn heikin_ashi(ohlcs: &[OHLC]) -> Vec<OHLC> {
+        let mut converter = HeikinAshi::new();
+        ohlcs.iter().map(|ohlc| converter.update(ohlc)).collect()
+    }
+
+    /// Converts live OHLC to Heikin-Ashi candles one at a time.
+    ///
+    /// Returns exactly what `heikin_ashi` returns for the same OHLC.
+    #[derive(Debug, Clone, Default)]
+    pub struct HeikinAshi {
+        /// The open and close of the previous Heikin-Ashi candle.
+        prev: Option<(f64, f64)>,
+    }
+
+    impl HeikinAshi {
+        /// Return a new HeikinAshi.
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Add the next OHLC, returning its Heikin-Ashi candle.
+        pub fn update(&mut self, ohlc: &OHLC) -> OHLC {
+            let close = (ohlc.open + ohlc.high + ohlc.low + ohlc.close) / 4.0;
+            let open = match self.prev {
+                Some((prev_open, prev_close)) => (prev_open + prev_close) / 2.0,
+                None => (ohlc.open + ohlc.close) / 2.0,
+            };
+            self.prev = Some((open, close));
+
+            OHLC {
+                open,
+                high: ohlc.high.max(open).max(close),
+                low: ohlc.low.min(open).min(close),
+                close,
+                vol: ohlc.vol,
+                ts: ohlc.ts,
+            }
+        }
+
+        /// Discard all state, as if no OHLC had been seen.
+        pub fn reset(&mut self) {
+            self.prev = None;
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn fixture() -> Vec<OHLC> {
+            vec![
+                OHLC::new(10.0, 12.0, 9.0, 11.0, 1625097600).with_volume(100.0),
+                OHLC::new(11.0, 14.0, 10.0, 13.0, 1625097660),
+                OHLC::new(13.0, 13.5, 9.0, 10.0, 1625097720).with_volume(300.0),
+                OHLC::new(10.0, 10.5, 8.0, 8.5, 1625097780).with_volume(400.0),
+            ]
+        }
+
+        #[test]
+        fn test_heikin_ashi() {
+            let result = heikin_ashi(&fixture());
+
+            assert_eq!(
+                result,
+                vec![
+                    OHLC::new(10.5, 12.0, 9.0, 10.5, 1625097600).with_volume(100.0),
+                    OHLC::new(10.5, 14.0, 10.0, 12.0, 1625097660),
+                    OHLC::new(11.25, 13.5, 9.0, 11.375, 1625097720).with_volume(300.0),
+                    OHLC::new(11.3125, 11.3125, 8.0, 9.25, 1625097780).with_volume(400.0),
+                ]
+            );
+
+            let directions: Vec<i8> = result.iter().map(|ohlc| ohlc.direction()).collect();
+            assert_eq!(directions, vec![0, 1, 1, -1]);
+            assert!(result.iter().all(|ohlc| ohlc.validate().is_ok()));
+        }
+
+        #[test]
+        fn test_heikin_ashi_empty() {
+            assert_eq!(heikin_ashi(&[]), vec![]);
+        }
+
+        #[test]
+        fn test_streaming() {
+            let ohlcs = fixture();
+            let expected = heikin_ashi(&ohlcs);
+            let mut converter = HeikinAshi::new();
+
+            for _ in 0..2 {
+                let result: Vec<OHLC> = ohlcs.iter().map(|ohlc| converter.update(ohlc)).collect();
+
+                assert_eq!(result, expected);
+                converter.reset();
+            }
+        }
+    }
+}
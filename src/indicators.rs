@@ -0,0 +1,815 @@
+pub mod indicators {
+    use crate::ohlc::ohlc::OHLC;
+
+    /// A single MACD value.
+    ///
+    /// The signal and histogram are `None` until the signal line has warmed up.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct MacdPoint {
+        pub macd: f64,
+        pub signal: Option<f64>,
+        pub histogram: Option<f64>,
+    }
+
+    /// A single set of Bollinger Bands.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct BandsPoint {
+        pub upper: f64,
+        pub middle: f64,
+        pub lower: f64,
+    }
+
+    /// A single Stochastic Oscillator value.
+    ///
+    /// `d` is `None` until enough `k` values are available.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct StochasticPoint {
+        pub k: f64,
+        pub d: Option<f64>,
+    }
+
+    /// A single Directional Movement value.
+    ///
+    /// `adx` is `None` until enough directional indexes are available.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct DmiPoint {
+        pub plus_di: f64,
+        pub minus_di: f64,
+        pub adx: Option<f64>,
+    }
+
+    /// Simple moving average.
+    ///
+    /// The first `period - 1` values are `None`.
+    pub fn sma(values: &[f64], period: usize) -> Vec<Option<f64>> {
+        (0..values.len())
+            .map(|i| window(values, i, period).map(mean))
+            .collect()
+    }
+
+    /// Exponential moving average with a smoothing factor of `2 / (period + 1)`.
+    ///
+    /// Seeded with the simple moving average of the first `period` values, so the first
+    /// `period - 1` values are `None`.
+    pub fn ema(values: &[f64], period: usize) -> Vec<Option<f64>> {
+        let alpha = 2.0 / (period as f64 + 1.0);
+        let mut prev: Option<f64> = None;
+
+        (0..values.len())
+            .map(|i| {
+                prev = match prev {
+                    Some(prev) => Some(ema_step(prev, values[i], alpha)),
+                    None => window(values, i, period).map(mean),
+                };
+                prev
+            })
+            .collect()
+    }
+
+    /// Linearly weighted moving average, with the most recent value weighted `period`.
+    ///
+    /// The first `period - 1` values are `None`.
+    pub fn wma(values: &[f64], period: usize) -> Vec<Option<f64>> {
+        (0..values.len())
+            .map(|i| window(values, i, period).map(weighted_mean))
+            .collect()
+    }
+
+    /// Relative Strength Index using Wilder's smoothing.
+    ///
+    /// The first `period` values are `None`, as the first average needs `period` changes.
+    pub fn rsi(values: &[f64], period: usize) -> Vec<Option<f64>> {
+        let mut out = vec![None; values.len()];
+        if period == 0 || values.len() <= period {
+            return out;
+        }
+
+        let changes: Vec<(f64, f64)> = values.windows(2).map(|w| gain_loss(w[1] - w[0])).collect();
+
+        let mut avg_gain = changes[..period].iter().map(|c| c.0).sum::<f64>() / period as f64;
+        let mut avg_loss = changes[..period].iter().map(|c| c.1).sum::<f64>() / period as f64;
+        out[period] = Some(rsi_value(avg_gain, avg_loss));
+
+        for i in period + 1..values.len() {
+            let (gain, loss) = changes[i - 1];
+            avg_gain = wilder_step(avg_gain, gain, period);
+            avg_loss = wilder_step(avg_loss, loss, period);
+            out[i] = Some(rsi_value(avg_gain, avg_loss));
+        }
+
+        out
+    }
+
+    /// Moving Average Convergence Divergence.
+    ///
+    /// The MACD line is the fast EMA less the slow EMA, and is defined once the slow EMA is. The
+    /// signal line is an EMA of the MACD line.
+    pub fn macd(values: &[f64], fast: usize, slow: usize, signal: usize) -> Vec<Option<MacdPoint>> {
+        let fast = ema(values, fast);
+        let slow = ema(values, slow);
+
+        let lines: Vec<Option<f64>> = fast
+            .iter()
+            .zip(&slow)
+            .map(|(f, s)| Some((*f)? - (*s)?))
+            .collect();
+
+        let start = lines
+            .iter()
+            .position(Option::is_some)
+            .unwrap_or(lines.len());
+        let defined: Vec<f64> = lines[start..].iter().flatten().copied().collect();
+        let signals = ema(&defined, signal);
+
+        let mut out = vec![None; values.len()];
+        for (j, (line, signal)) in defined.iter().zip(signals).enumerate() {
+            out[start + j] = Some(MacdPoint {
+                macd: *line,
+                signal,
+                histogram: signal.map(|s| line - s),
+            });
+        }
+        out
+    }
+
+    /// Bollinger Bands, `k` population standard deviations either side of the simple moving
+    /// average.
+    ///
+    /// The first `period - 1` values are `None`.
+    pub fn bollinger(values: &[f64], period: usize, k: f64) -> Vec<Option<BandsPoint>> {
+        (0..values.len())
+            .map(|i| window(values, i, period).map(|w| bands(w, k)))
+            .collect()
+    }
+
+    /// Average True Range using Wilder's smoothing.
+    ///
+    /// The true range needs a previous close, so the first ATR is the mean of the true ranges of
+    /// OHLC `1..=period`, and the first `period` values are `None`.
+    pub fn atr(ohlcs: &[OHLC], period: usize) -> Vec<Option<f64>> {
+        let mut out = vec![None; ohlcs.len()];
+        if period == 0 || ohlcs.len() <= period {
+            return out;
+        }
+
+        let ranges: Vec<f64> = ohlcs.windows(2).map(|w| true_range(&w[1], &w[0])).collect();
+
+        let mut value = mean(&ranges[..period]);
+        out[period] = Some(value);
+
+        for i in period + 1..ohlcs.len() {
+            value = wilder_step(value, ranges[i - 1], period);
+            out[i] = Some(value);
+        }
+
+        out
+    }
+
+    /// Stochastic Oscillator.
+    ///
+    /// `%K` is where the close sits within the high-low range of the last `k_period` OHLC, and is
+    /// 50 when that range is empty. `%D` is the simple moving average of `%K` over `d_period`.
+    pub fn stochastic(
+        ohlcs: &[OHLC],
+        k_period: usize,
+        d_period: usize,
+    ) -> Vec<Option<StochasticPoint>> {
+        let ks: Vec<Option<f64>> = (0..ohlcs.len())
+            .map(|i| window(ohlcs, i, k_period).map(percent_k))
+            .collect();
+
+        let start = ks.iter().position(Option::is_some).unwrap_or(ks.len());
+        let defined: Vec<f64> = ks[start..].iter().flatten().copied().collect();
+        let ds = sma(&defined, d_period);
+
+        let mut out = vec![None; ohlcs.len()];
+        for (j, (k, d)) in defined.iter().zip(ds).enumerate() {
+            out[start + j] = Some(StochasticPoint { k: *k, d });
+        }
+        out
+    }
+
+    /// Average Directional Index, with the directional indicators it is built from.
+    ///
+    /// Directional movement needs a previous OHLC, so the directional indicators start at index
+    /// `period` and the ADX, being the Wilder average of `period` directional indexes, at index
+    /// `2 * period - 1`.
+    pub fn adx(ohlcs: &[OHLC], period: usize) -> Vec<Option<DmiPoint>> {
+        let mut out = vec![None; ohlcs.len()];
+        if period == 0 || ohlcs.len() <= period {
+            return out;
+        }
+
+        let moves: Vec<(f64, f64, f64)> = ohlcs
+            .windows(2)
+            .map(|w| directional_move(&w[1], &w[0]))
+            .collect();
+
+        let mut plus = moves[..period].iter().map(|m| m.0).sum::<f64>();
+        let mut minus = moves[..period].iter().map(|m| m.1).sum::<f64>();
+        let mut range = moves[..period].iter().map(|m| m.2).sum::<f64>();
+        let mut dxs = Vec::with_capacity(period);
+        let mut adx: Option<f64> = None;
+
+        for i in period..ohlcs.len() {
+            if i > period {
+                let (p, m, r) = moves[i - 1];
+                plus = wilder_sum_step(plus, p, period);
+                minus = wilder_sum_step(minus, m, period);
+                range = wilder_sum_step(range, r, period);
+            }
+
+            let (plus_di, minus_di) = directional_indicators(plus, minus, range);
+            let dx = directional_index(plus_di, minus_di);
+
+            adx = match adx {
+                Some(prev) => Some(wilder_step(prev, dx, period)),
+                None => {
+                    dxs.push(dx);
+                    if dxs.len() == period {
+                        Some(mean(&dxs))
+                    } else {
+                        None
+                    }
+                }
+            };
+
+            out[i] = Some(DmiPoint {
+                plus_di,
+                minus_di,
+                adx,
+            });
+        }
+
+        out
+    }
+
+    /// Commodity Channel Index, using a constant of 0.015.
+    ///
+    /// Zero when the typical prices in the window have no deviation. The first `period - 1` values
+    /// are `None`.
+    pub fn cci(ohlcs: &[OHLC], period: usize) -> Vec<Option<f64>> {
+        let typical: Vec<f64> = ohlcs.iter().map(typical_price).collect();
+
+        (0..ohlcs.len())
+            .map(|i| window(&typical, i, period).map(cci_value))
+            .collect()
+    }
+
+    /// On Balance Volume, starting from zero. OHLC without a volume contribute nothing.
+    pub fn obv(ohlcs: &[OHLC]) -> Vec<f64> {
+        let mut total = 0.0;
+
+        ohlcs
+            .iter()
+            .enumerate()
+            .map(|(i, ohlc)| {
+                if i > 0 {
+                    total = obv_step(total, &ohlcs[i - 1], ohlc);
+                }
+                total
+            })
+            .collect()
+    }
+
+    /// Volume Weighted Average Price of the typical price, accumulated from the first OHLC.
+    ///
+    /// `None` until some volume has traded.
+    pub fn vwap(ohlcs: &[OHLC]) -> Vec<Option<f64>> {
+        let mut value = 0.0;
+        let mut volume = 0.0;
+
+        ohlcs
+            .iter()
+            .map(|ohlc| {
+                let vol = ohlc.vol.unwrap_or(0.0);
+                value += typical_price(ohlc) * vol;
+                volume += vol;
+                if volume > 0.0 {
+                    Some(value / volume)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// The `period` values ending at index `i`, if there are that many.
+    fn window<T>(values: &[T], i: usize, period: usize) -> Option<&[T]> {
+        if period == 0 || i + 1 < period {
+            None
+        } else {
+            Some(&values[i + 1 - period..=i])
+        }
+    }
+
+    pub(crate) fn mean(values: &[f64]) -> f64 {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+
+    pub(crate) fn weighted_mean(values: &[f64]) -> f64 {
+        let n = values.len() as f64;
+        let total: f64 = values
+            .iter()
+            .enumerate()
+            .map(|(j, v)| (j + 1) as f64 * v)
+            .sum();
+        total / (n * (n + 1.0) / 2.0)
+    }
+
+    pub(crate) fn ema_step(prev: f64, value: f64, alpha: f64) -> f64 {
+        alpha * value + (1.0 - alpha) * prev
+    }
+
+    pub(crate) fn wilder_step(prev: f64, value: f64, period: usize) -> f64 {
+        (prev * (period as f64 - 1.0) + value) / period as f64
+    }
+
+    pub(crate) fn wilder_sum_step(prev: f64, value: f64, period: usize) -> f64 {
+        prev - prev / period as f64 + value
+    }
+
+    pub(crate) fn gain_loss(change: f64) -> (f64, f64) {
+        (change.max(0.0), (-change).max(0.0))
+    }
+
+    pub(crate) fn rsi_value(avg_gain: f64, avg_loss: f64) -> f64 {
+        if avg_loss == 0.0 {
+            if avg_gain == 0.0 {
+                50.0
+            } else {
+                100.0
+            }
+        } else {
+            100.0 - 100.0 / (1.0 + avg_gain / avg_loss)
+        }
+    }
+
+    pub(crate) fn bands(values: &[f64], k: f64) -> BandsPoint {
+        let middle = mean(values);
+        let variance =
+            values.iter().map(|v| (v - middle).powi(2)).sum::<f64>() / values.len() as f64;
+        let width = k * variance.sqrt();
+
+        BandsPoint {
+            upper: middle + width,
+            middle,
+            lower: middle - width,
+        }
+    }
+
+    pub(crate) fn true_range(ohlc: &OHLC, prev: &OHLC) -> f64 {
+        (ohlc.high - ohlc.low)
+            .max((ohlc.high - prev.close).abs())
+            .max((ohlc.low - prev.close).abs())
+    }
+
+    pub(crate) fn percent_k(ohlcs: &[OHLC]) -> f64 {
+        let high = ohlcs.iter().map(|o| o.high).fold(f64::MIN, f64::max);
+        let low = ohlcs.iter().map(|o| o.low).fold(f64::MAX, f64::min);
+        let close = ohlcs[ohlcs.len() - 1].close;
+
+        if high == low {
+            50.0
+        } else {
+            100.0 * (close - low) / (high - low)
+        }
+    }
+
+    /// Positive directional movement, negative directional movement and true range.
+    pub(crate) fn directional_move(ohlc: &OHLC, prev: &OHLC) -> (f64, f64, f64) {
+        let up = ohlc.high - prev.high;
+        let down = prev.low - ohlc.low;
+        let plus = if up > down && up > 0.0 { up } else { 0.0 };
+        let minus = if down > up && down > 0.0 { down } else { 0.0 };
+        (plus, minus, true_range(ohlc, prev))
+    }
+
+    pub(crate) fn directional_indicators(plus: f64, minus: f64, range: f64) -> (f64, f64) {
+        if range == 0.0 {
+            (0.0, 0.0)
+        } else {
+            (100.0 * plus / range, 100.0 * minus / range)
+        }
+    }
+
+    pub(crate) fn directional_index(plus_di: f64, minus_di: f64) -> f64 {
+        let total = plus_di + minus_di;
+        if total == 0.0 {
+            0.0
+        } else {
+            100.0 * (plus_di - minus_di).abs() / total
+        }
+    }
+
+    pub(crate) fn typical_price(ohlc: &OHLC) -> f64 {
+        (ohlc.high + ohlc.low + ohlc.close) / 3.0
+    }
+
+    pub(crate) fn cci_value(typical: &[f64]) -> f64 {
+        let avg = mean(typical);
+        let deviation = typical.iter().map(|t| (t - avg).abs()).sum::<f64>() / typical.len() as f64;
+        let last = typical[typical.len() - 1];
+
+        if deviation == 0.0 {
+            0.0
+        } else {
+            (last - avg) / (0.015 * deviation)
+        }
+    }
+
+    pub(crate) fn obv_step(total: f64, prev: &OHLC, ohlc: &OHLC) -> f64 {
+        let vol = ohlc.vol.unwrap_or(0.0);
+        if ohlc.close > prev.close {
+            total + vol
+        } else if ohlc.close < prev.close {
+            total - vol
+        } else {
+            total
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const EPSILON: f64 = 1e-9;
+
+        // Closes from Wilder's RSI worked example, as published by StockCharts.
+        const CLOSES: [f64; 20] = [
+            44.34, 44.09, 44.15, 43.61, 44.33, 44.83, 45.10, 45.42, 45.84, 46.08, 45.89, 46.03,
+            45.61, 46.28, 46.28, 46.00, 46.03, 46.41, 46.22, 45.64,
+        ];
+
+        // A small OHLC fixture. Expected values were computed independently from the textbook
+        // definitions.
+        fn fixture() -> Vec<OHLC> {
+            let rows = [
+                (10.0, 11.0, 9.5, 10.5, 100.0),
+                (10.5, 11.5, 10.0, 11.0, 150.0),
+                (11.0, 12.0, 10.5, 11.8, 120.0),
+                (11.8, 12.2, 11.0, 11.2, 200.0),
+                (11.2, 11.6, 10.4, 10.6, 180.0),
+                (10.6, 11.0, 10.0, 10.9, 90.0),
+                (10.9, 12.4, 10.8, 12.2, 250.0),
+                (12.2, 12.9, 12.0, 12.6, 160.0),
+                (12.6, 12.8, 11.9, 12.0, 140.0),
+                (12.0, 12.5, 11.5, 12.4, 110.0),
+            ];
+
+            rows.iter()
+                .enumerate()
+                .map(|(i, &(o, h, l, c, v))| {
+                    OHLC::new(o, h, l, c, 1625097600 + 60 * i as u64).with_volume(v)
+                })
+                .collect()
+        }
+
+        fn assert_close(result: &[Option<f64>], expected: &[Option<f64>], name: &str) {
+            assert_eq!(result.len(), expected.len(), "{}: length mismatch", name);
+            for (i, (r, e)) in result.iter().zip(expected).enumerate() {
+                match (r, e) {
+                    (Some(r), Some(e)) => assert!(
+                        (r - e).abs() < EPSILON,
+                        "{}: index {} expected {}, got {}",
+                        name,
+                        i,
+                        e,
+                        r
+                    ),
+                    (None, None) => {}
+                    _ => panic!("{}: index {} expected {:?}, got {:?}", name, i, e, r),
+                }
+            }
+        }
+
+        #[test]
+        fn test_moving_averages() {
+            let values = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+
+            struct TestCase {
+                name: &'static str,
+                result: Vec<Option<f64>>,
+                expected: Vec<Option<f64>>,
+            }
+
+            let test_cases = vec![
+                TestCase {
+                    name: "sma",
+                    result: sma(&values, 3),
+                    expected: vec![None, None, Some(2.0), Some(3.0), Some(4.0), Some(5.0)],
+                },
+                TestCase {
+                    name: "ema",
+                    result: ema(&values, 3),
+                    expected: vec![None, None, Some(2.0), Some(3.0), Some(4.0), Some(5.0)],
+                },
+                TestCase {
+                    name: "ema lagging",
+                    result: ema(&[2.0, 4.0, 10.0, 10.0], 3),
+                    expected: vec![None, None, Some(16.0 / 3.0), Some(23.0 / 3.0)],
+                },
+                TestCase {
+                    name: "wma",
+                    result: wma(&values, 3),
+                    expected: vec![
+                        None,
+                        None,
+                        Some(14.0 / 6.0),
+                        Some(20.0 / 6.0),
+                        Some(26.0 / 6.0),
+                        Some(32.0 / 6.0),
+                    ],
+                },
+                TestCase {
+                    name: "zero period",
+                    result: sma(&values, 0),
+                    expected: vec![None; 6],
+                },
+                TestCase {
+                    name: "period longer than input",
+                    result: ema(&values, 10),
+                    expected: vec![None; 6],
+                },
+            ];
+
+            for case in test_cases {
+                assert_close(&case.result, &case.expected, case.name);
+            }
+        }
+
+        #[test]
+        fn test_rsi() {
+            let result = rsi(&CLOSES, 14);
+            let mut expected = vec![None; 14];
+            expected.extend(
+                [
+                    70.46413502,
+                    66.24961855,
+                    66.48094183,
+                    69.34685316,
+                    66.29471266,
+                    57.91502067,
+                ]
+                .map(Some),
+            );
+
+            for (i, (r, e)) in result.iter().zip(&expected).enumerate() {
+                match (r, e) {
+                    (Some(r), Some(e)) => assert!((r - e).abs() < 1e-6, "index {}", i),
+                    (None, None) => {}
+                    _ => panic!("index {} expected {:?}, got {:?}", i, e, r),
+                }
+            }
+        }
+
+        #[test]
+        fn test_rsi_flat() {
+            let result = rsi(&[1.0, 1.0, 1.0], 2);
+
+            assert_eq!(result, vec![None, None, Some(50.0)]);
+        }
+
+        #[test]
+        fn test_macd() {
+            let values = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+            let result = macd(&values, 2, 3, 2);
+
+            let expected = vec![
+                None,
+                None,
+                Some(MacdPoint {
+                    macd: 0.5,
+                    signal: None,
+                    histogram: None,
+                }),
+                Some(MacdPoint {
+                    macd: 0.5,
+                    signal: Some(0.5),
+                    histogram: Some(0.0),
+                }),
+                Some(MacdPoint {
+                    macd: 0.5,
+                    signal: Some(0.5),
+                    histogram: Some(0.0),
+                }),
+                Some(MacdPoint {
+                    macd: 0.5,
+                    signal: Some(0.5),
+                    histogram: Some(0.0),
+                }),
+            ];
+
+            assert_eq!(result.len(), expected.len());
+            for (i, (r, e)) in result.iter().zip(&expected).enumerate() {
+                match (r, e) {
+                    (Some(r), Some(e)) => {
+                        assert!((r.macd - e.macd).abs() < EPSILON, "macd at {}", i);
+                        assert_close(&[r.signal], &[e.signal], "signal");
+                        assert_close(&[r.histogram], &[e.histogram], "histogram");
+                    }
+                    (None, None) => {}
+                    _ => panic!("index {} expected {:?}, got {:?}", i, e, r),
+                }
+            }
+        }
+
+        #[test]
+        fn test_bollinger() {
+            let result = bollinger(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0], 8, 2.0);
+
+            let expected = BandsPoint {
+                upper: 9.0,
+                middle: 5.0,
+                lower: 1.0,
+            };
+
+            assert_eq!(result[..7], vec![None; 7]);
+            assert_eq!(result[7], Some(expected));
+        }
+
+        #[test]
+        fn test_atr() {
+            let result = atr(&fixture(), 3);
+            // True ranges are 1.5, 1.5, 1.2, 1.2, 1, 1.6, 0.9, 0.9 and 1.
+            let expected = vec![
+                None,
+                None,
+                None,
+                Some(7.0 / 5.0),
+                Some(4.0 / 3.0),
+                Some(11.0 / 9.0),
+                Some(182.0 / 135.0),
+                Some(971.0 / 810.0),
+                Some(2671.0 / 2430.0),
+                Some(3886.0 / 3645.0),
+            ];
+
+            assert_close(&result, &expected, "atr");
+        }
+
+        #[test]
+        fn test_stochastic() {
+            let result = stochastic(&fixture(), 3, 2);
+
+            let ks: Vec<Option<f64>> = result.iter().map(|p| p.map(|p| p.k)).collect();
+            let ds: Vec<Option<f64>> = result.iter().map(|p| p.and_then(|p| p.d)).collect();
+
+            assert_close(
+                &ks,
+                &[
+                    None,
+                    None,
+                    Some(92.0),
+                    Some(600.0 / 11.0),
+                    Some(100.0 / 9.0),
+                    Some(450.0 / 11.0),
+                    Some(275.0 / 3.0),
+                    Some(2600.0 / 29.0),
+                    Some(400.0 / 7.0),
+                    Some(450.0 / 7.0),
+                ],
+                "%K",
+            );
+            assert_close(
+                &ds,
+                &[
+                    None,
+                    None,
+                    None,
+                    Some(806.0 / 11.0),
+                    Some(3250.0 / 99.0),
+                    Some(2575.0 / 99.0),
+                    Some(4375.0 / 66.0),
+                    Some(15775.0 / 174.0),
+                    Some(14900.0 / 203.0),
+                    Some(425.0 / 7.0),
+                ],
+                "%D",
+            );
+        }
+
+        #[test]
+        fn test_adx() {
+            let result = adx(&fixture(), 3);
+
+            let plus: Vec<Option<f64>> = result.iter().map(|p| p.map(|p| p.plus_di)).collect();
+            let minus: Vec<Option<f64>> = result.iter().map(|p| p.map(|p| p.minus_di)).collect();
+            let adx: Vec<Option<f64>> = result.iter().map(|p| p.and_then(|p| p.adx)).collect();
+
+            // Directional indexes from index 3 are 100, 100/7, 20, 5500/103, 35500/547, 2516/47
+            // and 1300/151. The ADX averages the first three, then takes Wilder steps.
+            let step = |prev: f64, dx: f64| (2.0 * prev + dx) / 3.0;
+            let adx5 = (100.0 + 100.0 / 7.0 + 20.0) / 3.0;
+            let adx6 = step(adx5, 5500.0 / 103.0);
+            let adx7 = step(adx6, 35500.0 / 547.0);
+            let adx8 = step(adx7, 2516.0 / 47.0);
+            let adx9 = step(adx8, 1300.0 / 151.0);
+
+            assert_close(
+                &plus,
+                &[
+                    None,
+                    None,
+                    None,
+                    Some(200.0 / 7.0),
+                    Some(20.0),
+                    Some(160.0 / 11.0),
+                    Some(3950.0 / 91.0),
+                    Some(45100.0 / 971.0),
+                    Some(90200.0 / 2671.0),
+                    Some(45100.0 / 1943.0),
+                ],
+                "+DI",
+            );
+            assert_close(
+                &minus,
+                &[
+                    None,
+                    None,
+                    None,
+                    Some(0.0),
+                    Some(15.0),
+                    Some(240.0 / 11.0),
+                    Some(1200.0 / 91.0),
+                    Some(9600.0 / 971.0),
+                    Some(27300.0 / 2671.0),
+                    Some(37950.0 / 1943.0),
+                ],
+                "-DI",
+            );
+            assert_close(
+                &adx,
+                &[
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    Some(adx5),
+                    Some(adx6),
+                    Some(adx7),
+                    Some(adx8),
+                    Some(adx9),
+                ],
+                "ADX",
+            );
+        }
+
+        #[test]
+        fn test_cci() {
+            let result = cci(&fixture(), 3);
+            let expected = vec![
+                None,
+                None,
+                Some(100.0),
+                Some(2000.0 / 37.0),
+                Some(-100.0),
+                Some(-3200.0 / 43.0),
+                Some(100.0),
+                Some(1100.0 / 13.0),
+                Some(250.0 / 17.0),
+                Some(-1400.0 / 19.0),
+            ];
+
+            assert_close(&result, &expected, "cci");
+        }
+
+        #[test]
+        fn test_obv() {
+            let mut ohlcs = fixture();
+            ohlcs[5].vol = None;
+
+            let result = obv(&ohlcs);
+            let expected = vec![
+                0.0, 150.0, 270.0, 70.0, -110.0, -110.0, 140.0, 300.0, 160.0, 270.0,
+            ];
+
+            assert_eq!(result, expected, "OBV should match expected values");
+        }
+
+        #[test]
+        fn test_vwap() {
+            let ohlcs = vec![
+                OHLC::new(10.0, 12.0, 9.0, 9.0, 1000),
+                OHLC::new(10.0, 12.0, 9.0, 9.0, 1060).with_volume(100.0),
+                OHLC::new(13.0, 15.0, 12.0, 12.0, 1120).with_volume(300.0),
+            ];
+
+            assert!(ohlcs.iter().all(|ohlc| ohlc.validate().is_ok()));
+
+            let result = vwap(&ohlcs);
+
+            assert_eq!(result, vec![None, Some(10.0), Some(12.25)]);
+        }
+
+        #[test]
+        fn test_empty() {
+            assert_eq!(sma(&[], 3), vec![]);
+            assert_eq!(rsi(&[], 3), vec![]);
+            assert_eq!(atr(&[], 3), vec![]);
+            assert_eq!(adx(&[], 3), vec![]);
+            assert_eq!(obv(&[]), Vec::<f64>::new());
+        }
+    }
+}
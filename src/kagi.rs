@@ -0,0 +1,281 @@
+pub mod kagi {
+    use crate::charts::charts::Source;
+    use crate::ohlc::ohlc::OHLC;
+
+    /// The move needed to reverse a Kagi line.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub enum Reversal {
+        /// A fixed price amount.
+        Amount(f64),
+        /// A percentage of the last extreme, so 4.0 is 4%.
+        Percent(f64),
+    }
+
+    impl Reversal {
+        fn amount(&self, price: f64) -> f64 {
+            match self {
+                Reversal::Amount(amount) => *amount,
+                Reversal::Percent(percent) => price.abs() * percent / 100.0,
+            }
+        }
+    }
+
+    /// The direction of a segment.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum Direction {
+        Up,
+        Down,
+    }
+
+    /// The thickness of a Kagi line.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum Line {
+        /// A thick line, from price rising above the last shoulder.
+        Yang,
+        /// A thin line, from price falling below the last waist.
+        Yin,
+    }
+
+    /// A vertical segment of a Kagi chart.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct Segment {
+        pub direction: Direction,
+        pub start: f64,
+        pub end: f64,
+        /// The line at the end of the segment.
+        pub line: Line,
+        /// The price where the line changed thickness, if it did within the segment.
+        pub switch: Option<f64>,
+        /// The timestamp of the OHLC that started the segment.
+        pub start_ts: u64,
+        /// The timestamp of the OHLC that last extended the segment.
+        pub ts: u64,
+    }
+
+    /// A change of line thickness, a buy signal on turning yang and a sell signal on turning yin.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct Signal {
+        /// The index of the segment where the line changed.
+        pub segment: usize,
+        pub line: Line,
+        /// The shoulder or waist that was crossed.
+        pub price: f64,
+        pub ts: u64,
+    }
+
+    /// A Kagi chart, built one OHLC at a time.
+    ///
+    /// The line follows price in one direction and turns once price reverses by the reversal
+    /// amount from the extreme. It turns yang when price rises above the last shoulder, the top of
+    /// the previous up segment, and yin when price falls below the last waist, the bottom of the
+    /// previous down segment. The first segment is yang if it rises and yin if it falls.
+    #[derive(Debug, Clone)]
+    pub struct Kagi {
+        reversal: Reversal,
+        source: Source,
+        start: Option<f64>,
+        segments: Vec<Segment>,
+        shoulder: Option<f64>,
+        waist: Option<f64>,
+        signals: Vec<Signal>,
+    }
+
+    impl Kagi {
+        /// Return a new, empty chart.
+        pub fn new(reversal: Reversal, source: Source) -> Self {
+            Kagi {
+                reversal,
+                source,
+                start: None,
+                segments: Vec::new(),
+                shoulder: None,
+                waist: None,
+                signals: Vec::new(),
+            }
+        }
+
+        /// Builds a chart from a slice of OHLC.
+        pub fn from_ohlcs(ohlcs: &[OHLC], reversal: Reversal, source: Source) -> Self {
+            let mut chart = Self::new(reversal, source);
+            for ohlc in ohlcs {
+                chart.push(ohlc);
+            }
+            chart
+        }
+
+        /// Add an OHLC, returning any signals it triggered.
+        pub fn push(&mut self, ohlc: &OHLC) -> Vec<Signal> {
+            let before = self.signals.len();
+            for price in self.source.prices(ohlc) {
+                self.step(price, ohlc.ts);
+            }
+            self.signals[before..].to_vec()
+        }
+
+        /// The segments built so far.
+        pub fn segments(&self) -> &[Segment] {
+            &self.segments
+        }
+
+        /// The signals produced so far.
+        pub fn signals(&self) -> &[Signal] {
+            &self.signals
+        }
+
+        fn step(&mut self, price: f64, ts: u64) {
+            let Some(last) = self.segments.last().copied() else {
+                let start = *self.start.get_or_insert(price);
+                let amount = self.reversal.amount(start);
+                if amount > 0.0 && (price - start).abs() >= amount {
+                    let (direction, line) = if price > start {
+                        (Direction::Up, Line::Yang)
+                    } else {
+                        (Direction::Down, Line::Yin)
+                    };
+                    self.add(direction, start, price, line, ts);
+                }
+                return;
+            };
+
+            let amount = self.reversal.amount(last.end);
+            let extends = match last.direction {
+                Direction::Up => price > last.end,
+                Direction::Down => price < last.end,
+            };
+            let reverses = amount > 0.0
+                && match last.direction {
+                    Direction::Up => price <= last.end - amount,
+                    Direction::Down => price >= last.end + amount,
+                };
+
+            if extends {
+                let index = self.segments.len() - 1;
+                let segment = &mut self.segments[index];
+                segment.end = price;
+                segment.ts = ts;
+                self.check(index);
+            } else if reverses {
+                match last.direction {
+                    Direction::Up => self.shoulder = Some(last.end),
+                    Direction::Down => self.waist = Some(last.end),
+                }
+                let direction = match last.direction {
+                    Direction::Up => Direction::Down,
+                    Direction::Down => Direction::Up,
+                };
+                self.add(direction, last.end, price, last.line, ts);
+            }
+        }
+
+        fn add(&mut self, direction: Direction, start: f64, end: f64, line: Line, ts: u64) {
+            self.segments.push(Segment {
+                direction,
+                start,
+                end,
+                line,
+                switch: None,
+                start_ts: ts,
+                ts,
+            });
+            self.check(self.segments.len() - 1);
+        }
+
+        /// Changes the line of a segment if it has crossed the last shoulder or waist.
+        fn check(&mut self, index: usize) {
+            let segment = &mut self.segments[index];
+
+            let crossed = match (segment.line, segment.direction) {
+                (Line::Yin, Direction::Up) => self
+                    .shoulder
+                    .filter(|&shoulder| segment.end > shoulder)
+                    .map(|shoulder| (Line::Yang, shoulder)),
+                (Line::Yang, Direction::Down) => self
+                    .waist
+                    .filter(|&waist| segment.end < waist)
+                    .map(|waist| (Line::Yin, waist)),
+                _ => None,
+            };
+
+            if let Some((line, price)) = crossed {
+                segment.line = line;
+                segment.switch = Some(price);
+                self.signals.push(Signal {
+                    segment: index,
+                    line,
+                    price,
+                    ts: segment.ts,
+                });
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn closes(closes: &[f64]) -> Vec<OHLC> {
+            closes
+                .iter()
+                .enumerate()
+                .map(|(i, &c)| OHLC::new(c, c, c, c, i as u64 + 1))
+                .collect()
+        }
+
+        #[test]
+        fn test_kagi() {
+            let ohlcs = closes(&[
+                100.0, 101.0, 103.0, 102.0, 100.5, 99.0, 102.0, 100.0, 98.5, 101.0, 102.5,
+            ]);
+
+            let chart = Kagi::from_ohlcs(&ohlcs, Reversal::Amount(2.0), Source::Close);
+
+            let summary: Vec<(Direction, f64, f64, Line, Option<f64>)> = chart
+                .segments()
+                .iter()
+                .map(|s| (s.direction, s.start, s.end, s.line, s.switch))
+                .collect();
+            assert_eq!(
+                summary,
+                vec![
+                    (Direction::Up, 100.0, 103.0, Line::Yang, None),
+                    (Direction::Down, 103.0, 99.0, Line::Yang, None),
+                    (Direction::Up, 99.0, 102.0, Line::Yang, None),
+                    (Direction::Down, 102.0, 98.5, Line::Yin, Some(99.0)),
+                    (Direction::Up, 98.5, 102.5, Line::Yang, Some(102.0)),
+                ]
+            );
+            assert_eq!(
+                chart.signals(),
+                &[
+                    Signal {
+                        segment: 3,
+                        line: Line::Yin,
+                        price: 99.0,
+                        ts: 9
+                    },
+                    Signal {
+                        segment: 4,
+                        line: Line::Yang,
+                        price: 102.0,
+                        ts: 11
+                    },
+                ]
+            );
+            assert_eq!(chart.segments()[1].start_ts, 5);
+            assert_eq!(chart.segments()[1].ts, 6);
+        }
+
+        #[test]
+        fn test_percent_reversal() {
+            let ohlcs = closes(&[100.0, 96.0, 99.0, 101.0]);
+
+            let chart = Kagi::from_ohlcs(&ohlcs, Reversal::Percent(4.0), Source::Close);
+
+            assert_eq!(chart.segments().len(), 2);
+            assert_eq!(chart.segments()[0].line, Line::Yin);
+            assert_eq!(chart.segments()[1].start, 96.0);
+            assert_eq!(chart.segments()[1].end, 101.0);
+            assert!(chart.signals().is_empty(), "No shoulder to cross yet");
+        }
+    }
+}
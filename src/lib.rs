@@ -1,2 +1,27 @@
+#![allow(clippy::module_inception)]
+
 pub mod ohlc;
 pub mod models;
+pub mod resample;
+pub mod tick;
+pub mod series;
+pub mod indicators;
+pub mod streaming;
+pub mod backtest;
+pub mod patterns;
+#[cfg(feature = "csv")]
+pub mod csv;
+pub mod timestamp;
+pub mod timeframe;
+pub mod gaps;
+pub mod heikin_ashi;
+pub mod charts;
+pub mod pnf;
+pub mod kagi;
+pub mod volatility;
+pub mod returns;
+pub mod metrics;
+pub mod drawdown;
+pub mod risk;
+pub mod fees;
+pub mod slippage;
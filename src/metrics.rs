@@ -0,0 +1,399 @@
+pub mod metrics {
+    pub use crate::drawdown::drawdown::Drawdown;
+    use crate::drawdown::drawdown::{drawdowns, underwater};
+    use crate::indicators::indicators::mean;
+    use crate::returns::returns::Return;
+
+    /// Options for calculating metrics.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct Opts {
+        /// The number of periods in a year, such as 252 for daily returns.
+        pub periods_per_year: f64,
+        /// The annual risk free rate, used by the Sharpe ratio.
+        pub risk_free: f64,
+        /// The minimum acceptable return per period, used by the Sortino and Omega ratios.
+        pub target: f64,
+    }
+
+    impl Default for Opts {
+        fn default() -> Self {
+            Opts {
+                periods_per_year: 252.0,
+                risk_free: 0.0,
+                target: 0.0,
+            }
+        }
+    }
+
+    /// Performance metrics for a strategy.
+    ///
+    /// Ratios are None when undefined, such as a Sharpe ratio for returns with no variance.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct Metrics {
+        pub total_return: f64,
+        pub annualized_return: f64,
+        pub volatility: Option<f64>,
+        pub sharpe: Option<f64>,
+        pub sortino: Option<f64>,
+        pub calmar: Option<f64>,
+        pub omega: Option<f64>,
+        pub max_drawdown: Drawdown,
+        pub ulcer_index: f64,
+        pub skew: Option<f64>,
+        pub kurtosis: Option<f64>,
+        pub hit_ratio: f64,
+    }
+
+    /// Metrics for an equity curve of `(ts, equity)`, such as a backtest result.
+    ///
+    /// Returns None if there are fewer than two points.
+    pub fn from_equity(equity: &[(u64, f64)], opts: &Opts) -> Option<Metrics> {
+        if equity.len() < 2 {
+            return None;
+        }
+
+        calculate(&equity_returns(equity), equity, opts)
+    }
+
+    /// Metrics for a series of simple returns, compounded from a starting equity of one at
+    /// `start`, the timestamp the first return is measured from.
+    ///
+    /// Returns None if there are no returns.
+    pub fn from_returns(start: u64, returns: &[Return], opts: &Opts) -> Option<Metrics> {
+        if returns.is_empty() {
+            return None;
+        }
+
+        calculate(returns, &returns_equity(start, returns), opts)
+    }
+
+    fn calculate(returns: &[Return], equity: &[(u64, f64)], opts: &Opts) -> Option<Metrics> {
+        let values: Vec<f64> = returns.iter().map(|r| r.value).collect();
+        let ppy = opts.periods_per_year;
+        let annualized = annualized_return(&values, ppy);
+        let max_drawdown = max_drawdown(equity)?;
+
+        Some(Metrics {
+            total_return: total_return(&values),
+            annualized_return: annualized,
+            volatility: volatility(&values, ppy),
+            sharpe: sharpe(&values, opts.risk_free, ppy),
+            sortino: sortino(&values, opts.target, ppy),
+            calmar: (max_drawdown.depth > 0.0).then(|| annualized / max_drawdown.depth),
+            omega: omega(&values, opts.target),
+            max_drawdown,
+            ulcer_index: ulcer_index(equity),
+            skew: skew(&values),
+            kurtosis: kurtosis(&values),
+            hit_ratio: hit_ratio(&values),
+        })
+    }
+
+    /// Simple returns between consecutive points of an equity curve.
+    pub fn equity_returns(equity: &[(u64, f64)]) -> Vec<Return> {
+        equity
+            .windows(2)
+            .map(|w| Return::new(w[1].0, w[1].1 / w[0].1 - 1.0))
+            .collect()
+    }
+
+    /// An equity curve compounded from simple returns, starting from one at `start`, the
+    /// timestamp the first return is measured from.
+    pub fn returns_equity(start: u64, returns: &[Return]) -> Vec<(u64, f64)> {
+        let mut equity = 1.0;
+
+        std::iter::once((start, equity))
+            .chain(returns.iter().map(|r| {
+                equity *= 1.0 + r.value;
+                (r.ts, equity)
+            }))
+            .collect()
+    }
+
+    /// The compounded return of a series of simple returns.
+    pub fn total_return(returns: &[f64]) -> f64 {
+        returns.iter().fold(1.0, |total, r| total * (1.0 + r)) - 1.0
+    }
+
+    /// The compound annual growth rate of a series of simple returns.
+    pub fn annualized_return(returns: &[f64], periods_per_year: f64) -> f64 {
+        if returns.is_empty() {
+            return 0.0;
+        }
+        (1.0 + total_return(returns)).powf(periods_per_year / returns.len() as f64) - 1.0
+    }
+
+    /// The annualized sample standard deviation of returns.
+    pub fn volatility(returns: &[f64], periods_per_year: f64) -> Option<f64> {
+        std_dev(returns).map(|sd| sd * periods_per_year.sqrt())
+    }
+
+    /// The annualized Sharpe ratio, with the annual risk free rate spread evenly over periods.
+    pub fn sharpe(returns: &[f64], risk_free: f64, periods_per_year: f64) -> Option<f64> {
+        let sd = std_dev(returns).filter(|&sd| sd > 0.0)?;
+        let excess = mean(returns) - risk_free / periods_per_year;
+        Some(excess / sd * periods_per_year.sqrt())
+    }
+
+    /// The annualized Sortino ratio, using the downside deviation below `target` over all
+    /// periods.
+    pub fn sortino(returns: &[f64], target: f64, periods_per_year: f64) -> Option<f64> {
+        if returns.is_empty() {
+            return None;
+        }
+        let downside: Vec<f64> = returns
+            .iter()
+            .map(|r| (r - target).min(0.0).powi(2))
+            .collect();
+        let deviation = mean(&downside).sqrt();
+        if deviation == 0.0 {
+            return None;
+        }
+        Some((mean(returns) - target) / deviation * periods_per_year.sqrt())
+    }
+
+    /// The ratio of gains above `target` to losses below it.
+    pub fn omega(returns: &[f64], target: f64) -> Option<f64> {
+        let gains: f64 = returns.iter().map(|r| (r - target).max(0.0)).sum();
+        let losses: f64 = returns.iter().map(|r| (target - r).max(0.0)).sum();
+        if losses == 0.0 {
+            None
+        } else {
+            Some(gains / losses)
+        }
+    }
+
+    /// The deepest drawdown of an equity curve, or None if there are no points.
+    ///
+    /// The earliest is returned if several share the greatest depth, and a drawdown of zero
+    /// depth at the first point if equity never falls.
+    pub fn max_drawdown(equity: &[(u64, f64)]) -> Option<Drawdown> {
+        let first_ts = equity.first()?.0;
+        let none = Drawdown {
+            depth: 0.0,
+            peak_ts: first_ts,
+            trough_ts: first_ts,
+            recovery_ts: Some(first_ts),
+            duration: 0,
+        };
+
+        Some(
+            drawdowns(equity)
+                .into_iter()
+                .fold(none, |max, dd| if dd.depth > max.depth { dd } else { max }),
+        )
+    }
+
+    /// The root mean square of the drawdown from the running peak, as a fraction.
+    ///
+    /// Multiply by 100 for the conventional percentage form.
+    pub fn ulcer_index(equity: &[(u64, f64)]) -> f64 {
+        if equity.is_empty() {
+            return 0.0;
+        }
+
+        let squares: Vec<f64> = underwater(equity)
+            .iter()
+            .map(|(_, value)| value * value)
+            .collect();
+        mean(&squares).sqrt()
+    }
+
+    /// The skewness of returns, from population moments.
+    pub fn skew(returns: &[f64]) -> Option<f64> {
+        let (m2, m3, _) = moments(returns)?;
+        Some(m3 / m2.powf(1.5))
+    }
+
+    /// The excess kurtosis of returns, from population moments.
+    pub fn kurtosis(returns: &[f64]) -> Option<f64> {
+        let (m2, _, m4) = moments(returns)?;
+        Some(m4 / (m2 * m2) - 3.0)
+    }
+
+    /// The fraction of returns that are positive.
+    pub fn hit_ratio(returns: &[f64]) -> f64 {
+        if returns.is_empty() {
+            return 0.0;
+        }
+        returns.iter().filter(|&&r| r > 0.0).count() as f64 / returns.len() as f64
+    }
+
+    pub(crate) fn std_dev(returns: &[f64]) -> Option<f64> {
+        if returns.len() < 2 {
+            return None;
+        }
+        let avg = mean(returns);
+        let sum: f64 = returns.iter().map(|r| (r - avg).powi(2)).sum();
+        Some((sum / (returns.len() - 1) as f64).sqrt())
+    }
+
+    /// The second, third and fourth central moments, or None if there is no variance.
+    fn moments(returns: &[f64]) -> Option<(f64, f64, f64)> {
+        if returns.is_empty() {
+            return None;
+        }
+        let avg = mean(returns);
+        let moment = |p: i32| {
+            mean(
+                &returns
+                    .iter()
+                    .map(|r| (r - avg).powi(p))
+                    .collect::<Vec<_>>(),
+            )
+        };
+        let m2 = moment(2);
+        if m2 == 0.0 {
+            return None;
+        }
+        Some((m2, moment(3), moment(4)))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const EPSILON: f64 = 1e-12;
+
+        fn assert_close(result: Option<f64>, expected: f64, name: &str) {
+            let result = result.unwrap_or_else(|| panic!("{} should have a value", name));
+            assert!(
+                (result - expected).abs() < EPSILON * expected.abs().max(1.0),
+                "{}: got {}, expected {}",
+                name,
+                result,
+                expected
+            );
+        }
+
+        fn fixture() -> Vec<(u64, f64)> {
+            vec![
+                (1, 100.0),
+                (2, 110.0),
+                (3, 99.0),
+                (4, 104.5),
+                (5, 115.5),
+                (6, 112.0),
+                (7, 117.6),
+            ]
+        }
+
+        #[test]
+        fn test_from_equity() {
+            let m = from_equity(&fixture(), &Opts::default()).unwrap();
+
+            // Reference values calculated independently.
+            assert_close(Some(m.total_return), 0.176, "total_return");
+            assert_close(
+                Some(m.annualized_return),
+                1.176f64.powf(42.0) - 1.0,
+                "annualized_return",
+            );
+            assert_close(m.volatility, 1.2730136060199306, "volatility");
+            assert_close(m.sharpe, 5.955677658378713, "sharpe");
+            assert_close(m.sortino, 11.195992452357796, "sortino");
+            assert_close(m.omega, 2.3853529171766654, "omega");
+            assert_close(Some(m.ulcer_index), 0.04378237356606027, "ulcer_index");
+            assert_close(m.skew, -0.6927215436376315, "skew");
+            assert_close(m.kurtosis, -0.9335567744656279, "kurtosis");
+            assert_close(Some(m.hit_ratio), 4.0 / 6.0, "hit_ratio");
+            assert_close(m.calmar, (1.176f64.powf(42.0) - 1.0) / 0.1, "calmar");
+
+            let dd = m.max_drawdown;
+            assert!((dd.depth - 0.1).abs() < EPSILON);
+            assert_eq!(
+                (dd.peak_ts, dd.trough_ts, dd.recovery_ts, dd.duration),
+                (2, 3, Some(5), 3)
+            );
+        }
+
+        #[test]
+        fn test_from_returns() {
+            let equity = fixture();
+            let returns = equity_returns(&equity);
+
+            let by_returns = from_returns(1, &returns, &Opts::default()).unwrap();
+            let by_equity = from_equity(&equity, &Opts::default()).unwrap();
+
+            assert_close(
+                Some(by_returns.total_return),
+                by_equity.total_return,
+                "total_return",
+            );
+            assert_eq!(by_returns.sharpe, by_equity.sharpe);
+            assert_eq!(by_returns.max_drawdown, by_equity.max_drawdown);
+            assert_eq!(returns_equity(1, &returns).len(), returns.len() + 1);
+            assert_eq!(from_returns(1, &[], &Opts::default()), None);
+        }
+
+        #[test]
+        fn test_from_returns_falling_first() {
+            let returns = vec![Return::new(2, -0.1), Return::new(3, 0.2)];
+
+            let dd = from_returns(1, &returns, &Opts::default())
+                .unwrap()
+                .max_drawdown;
+
+            assert!((dd.depth - 0.1).abs() < EPSILON);
+            assert_eq!(
+                (dd.peak_ts, dd.trough_ts, dd.recovery_ts, dd.duration),
+                (1, 2, Some(3), 2)
+            );
+            assert_eq!(
+                returns_equity(1, &returns)
+                    .iter()
+                    .map(|p| p.0)
+                    .collect::<Vec<_>>(),
+                vec![1, 2, 3]
+            );
+        }
+
+        #[test]
+        fn test_max_drawdown() {
+            let test_cases = vec![
+                ("empty", vec![], None),
+                (
+                    "rising",
+                    vec![(1, 100.0), (2, 101.0)],
+                    Some((0.0, 1, 1, Some(1), 0)),
+                ),
+                (
+                    "ongoing",
+                    vec![(1, 100.0), (2, 120.0), (3, 90.0), (4, 100.0)],
+                    Some((0.25, 2, 3, None, 2)),
+                ),
+                (
+                    "deeper later",
+                    vec![(1, 100.0), (2, 95.0), (3, 101.0), (4, 80.8), (5, 102.0)],
+                    Some((0.2, 3, 4, Some(5), 2)),
+                ),
+            ];
+
+            for (name, equity, expected) in test_cases {
+                let result = max_drawdown(&equity).map(|dd| {
+                    (
+                        (dd.depth * 1e9).round() / 1e9,
+                        dd.peak_ts,
+                        dd.trough_ts,
+                        dd.recovery_ts,
+                        dd.duration,
+                    )
+                });
+
+                assert_eq!(result, expected, "Test case '{}' failed", name);
+            }
+        }
+
+        #[test]
+        fn test_undefined() {
+            let flat = [0.01, 0.01, 0.01];
+
+            assert_eq!(sharpe(&flat, 0.0, 252.0), None);
+            assert_eq!(sortino(&flat, 0.0, 252.0), None);
+            assert_eq!(omega(&flat, 0.0), None);
+            assert_eq!(skew(&flat), None);
+            assert_eq!(volatility(&[0.01], 252.0), None);
+            assert_eq!(from_equity(&[(1, 100.0)], &Opts::default()), None);
+        }
+    }
+}
@@ -1,2 +1,1153 @@
 pub mod models {
+    use crate::indicators::indicators::atr;
+    use crate::ohlc::ohlc::OHLC;
+    use std::collections::BTreeMap;
+    use std::fmt;
+
+    pub use crate::tick::tick::Side;
+
+    /// A ticker symbol.
+    #[derive(Debug, PartialEq, Eq, Hash, Clone, PartialOrd, Ord)]
+    pub struct Symbol(String);
+
+    impl Symbol {
+        /// Return a new Symbol.
+        pub fn new(symbol: impl Into<String>) -> Self {
+            Symbol(symbol.into())
+        }
+
+        /// Returns the symbol as a string slice.
+        pub fn as_str(&self) -> &str {
+            &self.0
+        }
+    }
+
+    impl fmt::Display for Symbol {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl From<&str> for Symbol {
+        fn from(symbol: &str) -> Self {
+            Symbol::new(symbol)
+        }
+    }
+
+    /// A tradeable instrument.
+    ///
+    /// A tick size, lot size or minimum quantity of zero means there is no constraint.
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct Instrument {
+        pub symbol: Symbol,
+        pub currency: String,
+        pub tick_size: f64,
+        pub lot_size: f64,
+        pub min_quantity: f64,
+        pub multiplier: f64,
+    }
+
+    impl Instrument {
+        /// Return a new, unconstrained Instrument with a multiplier of one.
+        pub fn new(symbol: impl Into<Symbol>, currency: impl Into<String>) -> Self {
+            Instrument {
+                symbol: symbol.into(),
+                currency: currency.into(),
+                tick_size: 0.0,
+                lot_size: 0.0,
+                min_quantity: 0.0,
+                multiplier: 1.0,
+            }
+        }
+
+        /// Specify the tick size for an Instrument.
+        pub fn with_tick_size(mut self, tick_size: f64) -> Self {
+            self.tick_size = tick_size;
+            self
+        }
+
+        /// Specify the lot size for an Instrument.
+        pub fn with_lot_size(mut self, lot_size: f64) -> Self {
+            self.lot_size = lot_size;
+            self
+        }
+
+        /// Specify the minimum order quantity for an Instrument.
+        pub fn with_min_quantity(mut self, min_quantity: f64) -> Self {
+            self.min_quantity = min_quantity;
+            self
+        }
+
+        /// Specify the contract multiplier for an Instrument.
+        pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+            self.multiplier = multiplier;
+            self
+        }
+
+        /// Rounds a price to the nearest tick.
+        pub fn round_price(&self, price: f64) -> f64 {
+            if self.tick_size > 0.0 {
+                (price / self.tick_size).round() * self.tick_size
+            } else {
+                price
+            }
+        }
+
+        /// Rounds a quantity down to a whole number of lots.
+        ///
+        /// Returns zero if the rounded quantity is below the minimum quantity.
+        pub fn round_quantity(&self, quantity: f64) -> f64 {
+            let quantity = if self.lot_size > 0.0 {
+                // Guard against values like 2.9999999 lots from floating point division.
+                (quantity / self.lot_size + 1e-9).floor() * self.lot_size
+            } else {
+                quantity
+            };
+
+            if quantity < self.min_quantity || quantity <= 0.0 {
+                0.0
+            } else {
+                quantity
+            }
+        }
+    }
+
+    /// How an order is priced.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub enum OrderType {
+        Market,
+        Limit { price: f64 },
+        Stop { price: f64 },
+        StopLimit { stop: f64, limit: f64 },
+    }
+
+    /// How long an order remains working.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum TimeInForce {
+        /// Until the end of the bar it is first eligible to fill in.
+        Day,
+        /// Good till cancelled.
+        Gtc,
+        /// Immediate or cancel, allowing a partial fill.
+        Ioc,
+        /// Fill or kill, filling completely or not at all.
+        Fok,
+        /// Good till the timestamp, inclusive.
+        Gtd(u64),
+    }
+
+    /// An instruction to trade.
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct Order {
+        pub id: u64,
+        pub symbol: Symbol,
+        pub side: Side,
+        pub quantity: f64,
+        pub order_type: OrderType,
+        pub time_in_force: TimeInForce,
+        pub ts: u64,
+    }
+
+    impl Order {
+        /// Return a new Order, good till cancelled.
+        pub fn new(
+            symbol: impl Into<Symbol>,
+            side: Side,
+            quantity: f64,
+            order_type: OrderType,
+            ts: u64,
+        ) -> Self {
+            Order {
+                id: 0,
+                symbol: symbol.into(),
+                side,
+                quantity,
+                order_type,
+                time_in_force: TimeInForce::Gtc,
+                ts,
+            }
+        }
+
+        /// Return a new market Order.
+        pub fn market(symbol: impl Into<Symbol>, side: Side, quantity: f64, ts: u64) -> Self {
+            Self::new(symbol, side, quantity, OrderType::Market, ts)
+        }
+
+        /// Return a new limit Order.
+        pub fn limit(
+            symbol: impl Into<Symbol>,
+            side: Side,
+            quantity: f64,
+            price: f64,
+            ts: u64,
+        ) -> Self {
+            Self::new(symbol, side, quantity, OrderType::Limit { price }, ts)
+        }
+
+        /// Return a new stop Order.
+        pub fn stop(
+            symbol: impl Into<Symbol>,
+            side: Side,
+            quantity: f64,
+            price: f64,
+            ts: u64,
+        ) -> Self {
+            Self::new(symbol, side, quantity, OrderType::Stop { price }, ts)
+        }
+
+        /// Return a new stop limit Order.
+        pub fn stop_limit(
+            symbol: impl Into<Symbol>,
+            side: Side,
+            quantity: f64,
+            stop: f64,
+            limit: f64,
+            ts: u64,
+        ) -> Self {
+            Self::new(
+                symbol,
+                side,
+                quantity,
+                OrderType::StopLimit { stop, limit },
+                ts,
+            )
+        }
+
+        /// Specify the id for an Order.
+        pub fn with_id(mut self, id: u64) -> Self {
+            self.id = id;
+            self
+        }
+
+        /// Specify the time in force for an Order.
+        pub fn with_time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+            self.time_in_force = time_in_force;
+            self
+        }
+
+        /// Returns true if the order has expired by the given timestamp.
+        pub fn is_expired(&self, ts: u64) -> bool {
+            match self.time_in_force {
+                TimeInForce::Gtd(expiry) => ts > expiry,
+                _ => false,
+            }
+        }
+    }
+
+    /// Whether a fill added liquidity to the order book or removed it.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum Liquidity {
+        /// A resting order that was filled.
+        Maker,
+        /// An order that filled against a resting order.
+        Taker,
+    }
+
+    /// A full or partial execution of an order.
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct Fill {
+        pub order_id: u64,
+        pub symbol: Symbol,
+        pub side: Side,
+        pub price: f64,
+        pub quantity: f64,
+        pub fee: f64,
+        pub liquidity: Liquidity,
+        pub ts: u64,
+    }
+
+    /// Executions are fills by another name.
+    pub type Execution = Fill;
+
+    impl Fill {
+        /// Return a new Fill without a fee, taking liquidity.
+        pub fn new(
+            order_id: u64,
+            symbol: impl Into<Symbol>,
+            side: Side,
+            price: f64,
+            quantity: f64,
+            ts: u64,
+        ) -> Self {
+            Fill {
+                order_id,
+                symbol: symbol.into(),
+                side,
+                price,
+                quantity,
+                fee: 0.0,
+                liquidity: Liquidity::Taker,
+                ts,
+            }
+        }
+
+        /// Return a Fill of an order at a price, stamped with the timestamp of the OHLC it
+        /// filled in.
+        pub fn from_order(order: &Order, price: f64, quantity: f64, ohlc: &OHLC) -> Self {
+            Self::new(
+                order.id,
+                order.symbol.clone(),
+                order.side,
+                price,
+                quantity,
+                ohlc.ts,
+            )
+        }
+
+        /// Specify the fee for a Fill.
+        pub fn with_fee(mut self, fee: f64) -> Self {
+            self.fee = fee;
+            self
+        }
+
+        /// Specify whether a Fill added or removed liquidity.
+        pub fn with_liquidity(mut self, liquidity: Liquidity) -> Self {
+            self.liquidity = liquidity;
+            self
+        }
+
+        /// Returns the quantity, negative for a sell.
+        pub fn signed_quantity(&self) -> f64 {
+            self.side.sign() * self.quantity
+        }
+
+        /// Returns the traded value, price multiplied by quantity.
+        pub fn notional(&self) -> f64 {
+            self.price * self.quantity
+        }
+
+        /// Returns the index of the OHLC the fill happened in, being the last OHLC at or before
+        /// the fill timestamp. The OHLC must be sorted by timestamp.
+        pub fn bar_index(&self, ohlcs: &[OHLC]) -> Option<usize> {
+            ohlcs
+                .partition_point(|ohlc| ohlc.ts <= self.ts)
+                .checked_sub(1)
+        }
+    }
+
+    /// A holding in a single instrument.
+    ///
+    /// The quantity is negative when short. P&L is in price units multiplied by quantity and the
+    /// multiplier, and excludes fees, which are accumulated separately.
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct Position {
+        pub symbol: Symbol,
+        pub quantity: f64,
+        pub avg_price: f64,
+        pub realized_pnl: f64,
+        pub fees: f64,
+        pub multiplier: f64,
+    }
+
+    impl Position {
+        /// Return a new, flat Position with a multiplier of one.
+        pub fn new(symbol: impl Into<Symbol>) -> Self {
+            Position {
+                symbol: symbol.into(),
+                quantity: 0.0,
+                avg_price: 0.0,
+                realized_pnl: 0.0,
+                fees: 0.0,
+                multiplier: 1.0,
+            }
+        }
+
+        /// Specify the contract multiplier for a Position.
+        pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+            self.multiplier = multiplier;
+            self
+        }
+
+        /// Returns true if there is no quantity held.
+        pub fn is_flat(&self) -> bool {
+            self.quantity == 0.0
+        }
+
+        /// Update the position with a fill.
+        ///
+        /// Fills that add to the position update the average price. Fills that reduce it realize
+        /// P&L against the average price, and any quantity beyond flat opens a new position at the
+        /// fill price. A fill with no quantity only adds its fee.
+        pub fn apply(&mut self, fill: &Fill) {
+            let delta = fill.signed_quantity();
+            self.fees += fill.fee;
+            if delta == 0.0 {
+                return;
+            }
+
+            if self.quantity == 0.0 || self.quantity.signum() == delta.signum() {
+                let quantity = self.quantity + delta;
+                self.avg_price = (self.avg_price * self.quantity + fill.price * delta) / quantity;
+                self.quantity = quantity;
+                return;
+            }
+
+            let closed = delta.abs().min(self.quantity.abs());
+            self.realized_pnl +=
+                closed * (fill.price - self.avg_price) * self.quantity.signum() * self.multiplier;
+
+            let quantity = self.quantity + delta;
+            if quantity == 0.0 {
+                self.avg_price = 0.0;
+            } else if quantity.signum() != self.quantity.signum() {
+                self.avg_price = fill.price;
+            }
+            self.quantity = quantity;
+        }
+
+        /// Returns the P&L of the open quantity at a price.
+        pub fn unrealized_pnl(&self, price: f64) -> f64 {
+            self.quantity * (price - self.avg_price) * self.multiplier
+        }
+
+        /// Returns the value of the open quantity at a price, negative when short.
+        pub fn market_value(&self, price: f64) -> f64 {
+            self.quantity * price * self.multiplier
+        }
+
+        /// Returns the P&L of the open quantity at the close of an OHLC.
+        pub fn mark(&self, ohlc: &OHLC) -> f64 {
+            self.unrealized_pnl(ohlc.close)
+        }
+    }
+
+    /// The account and market state used to size an order.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct SizingInput {
+        pub equity: f64,
+        pub price: f64,
+        /// The distance from the entry price to the stop, used by fixed risk sizing.
+        pub stop_distance: Option<f64>,
+        /// The annualized volatility of the instrument's returns, used by volatility targeting.
+        pub volatility: Option<f64>,
+    }
+
+    impl SizingInput {
+        /// Return a new SizingInput for an account's equity and the entry price.
+        pub fn new(equity: f64, price: f64) -> Self {
+            SizingInput {
+                equity,
+                price,
+                stop_distance: None,
+                volatility: None,
+            }
+        }
+
+        /// Specify the stop distance for a SizingInput.
+        pub fn with_stop_distance(mut self, stop_distance: f64) -> Self {
+            self.stop_distance = Some(stop_distance);
+            self
+        }
+
+        /// Specify the annualized volatility for a SizingInput.
+        pub fn with_volatility(mut self, volatility: f64) -> Self {
+            self.volatility = Some(volatility);
+            self
+        }
+    }
+
+    /// Returns a stop distance of `multiple` times the latest ATR, or None if there are too few
+    /// OHLC for the period.
+    pub fn atr_stop_distance(ohlcs: &[OHLC], period: usize, multiple: f64) -> Option<f64> {
+        atr(ohlcs, period)
+            .last()
+            .copied()
+            .flatten()
+            .map(|atr| atr * multiple)
+    }
+
+    /// How the quantity of an order is chosen.
+    ///
+    /// Fractions are of equity, so 0.02 is 2%.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub enum Sizing {
+        /// A fixed quantity.
+        Fixed(f64),
+        /// A notional value of a fraction of equity.
+        FixedFractional(f64),
+        /// Loses a fraction of equity if the stop is hit.
+        FixedRisk(f64),
+        /// A position whose annualized volatility is the target fraction of equity.
+        VolatilityTarget(f64),
+        /// A fraction of the Kelly criterion for a win rate and a ratio of the average win to the
+        /// average loss, with the result as a fraction of equity in notional value.
+        Kelly {
+            fraction: f64,
+            win_rate: f64,
+            payoff: f64,
+        },
+    }
+
+    impl Sizing {
+        /// Returns the quantity to trade, rounded to the instrument's lots.
+        ///
+        /// Returns zero if the quantity is below the instrument's minimum, or if the input is
+        /// missing the stop distance or volatility the sizing needs.
+        pub fn quantity(&self, instrument: &Instrument, input: &SizingInput) -> f64 {
+            let notional =
+                |fraction: f64| input.equity * fraction / (input.price * instrument.multiplier);
+
+            let quantity = match *self {
+                Sizing::Fixed(quantity) => quantity,
+                Sizing::FixedFractional(fraction) => notional(fraction),
+                Sizing::FixedRisk(fraction) => match input.stop_distance {
+                    Some(distance) => input.equity * fraction / (distance * instrument.multiplier),
+                    None => 0.0,
+                },
+                Sizing::VolatilityTarget(target) => match input.volatility {
+                    Some(volatility) => notional(target / volatility),
+                    None => 0.0,
+                },
+                Sizing::Kelly {
+                    fraction,
+                    win_rate,
+                    payoff,
+                } => notional(fraction * (win_rate - (1.0 - win_rate) / payoff)),
+            };
+
+            if quantity.is_finite() {
+                instrument.round_quantity(quantity)
+            } else {
+                0.0
+            }
+        }
+
+        /// Returns a market order for a signal, or None if the quantity is zero.
+        pub fn order(
+            &self,
+            instrument: &Instrument,
+            side: Side,
+            input: &SizingInput,
+            ts: u64,
+        ) -> Option<Order> {
+            let quantity = self.quantity(instrument, input);
+            if quantity > 0.0 {
+                Some(Order::market(instrument.symbol.clone(), side, quantity, ts))
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Errors returned by a Portfolio.
+    #[derive(Debug, PartialEq, Eq, Clone)]
+    pub enum PortfolioError {
+        /// The instrument has not been added to the portfolio.
+        UnknownInstrument { symbol: Symbol },
+        /// There is no rate to convert the currency to the base currency.
+        MissingRate { currency: String },
+    }
+
+    impl fmt::Display for PortfolioError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                PortfolioError::UnknownInstrument { symbol } => {
+                    write!(f, "Instrument {} is not in the portfolio", symbol)
+                }
+                PortfolioError::MissingRate { currency } => {
+                    write!(f, "No exchange rate for {}", currency)
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for PortfolioError {}
+
+    /// The value of a portfolio at a point in time, in the base currency.
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct Snapshot {
+        pub ts: u64,
+        /// The total of cash held in every currency.
+        pub cash: f64,
+        /// Cash plus the market value of every position.
+        pub equity: f64,
+        /// The sum of the absolute market values of positions.
+        pub gross_exposure: f64,
+        /// The sum of the market values of positions, negative when net short.
+        pub net_exposure: f64,
+        /// The market value of each open position, by symbol.
+        pub exposures: Vec<(Symbol, f64)>,
+    }
+
+    /// Cash and positions across many instruments and currencies.
+    ///
+    /// Positions are valued at the latest close marked for their instrument, or at their average
+    /// price before the first mark. A rate is the value of one unit of a currency in the base
+    /// currency.
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct Portfolio {
+        base_currency: String,
+        instruments: BTreeMap<Symbol, Instrument>,
+        cash: BTreeMap<String, f64>,
+        positions: BTreeMap<Symbol, Position>,
+        prices: BTreeMap<Symbol, f64>,
+        rates: BTreeMap<String, f64>,
+        snapshots: Vec<Snapshot>,
+    }
+
+    impl Portfolio {
+        /// Return a new, empty Portfolio valued in a base currency.
+        pub fn new(base_currency: impl Into<String>) -> Self {
+            Portfolio {
+                base_currency: base_currency.into(),
+                instruments: BTreeMap::new(),
+                cash: BTreeMap::new(),
+                positions: BTreeMap::new(),
+                prices: BTreeMap::new(),
+                rates: BTreeMap::new(),
+                snapshots: Vec::new(),
+            }
+        }
+
+        /// Specify an instrument the Portfolio can trade.
+        pub fn with_instrument(mut self, instrument: Instrument) -> Self {
+            self.instruments
+                .insert(instrument.symbol.clone(), instrument);
+            self
+        }
+
+        /// Specify cash held by the Portfolio.
+        pub fn with_cash(mut self, currency: impl Into<String>, amount: f64) -> Self {
+            self.deposit(currency, amount);
+            self
+        }
+
+        /// Specify the exchange rate for a currency.
+        pub fn with_rate(mut self, currency: impl Into<String>, rate: f64) -> Self {
+            self.set_rate(currency, rate);
+            self
+        }
+
+        /// Update the exchange rate for a currency.
+        pub fn set_rate(&mut self, currency: impl Into<String>, rate: f64) {
+            self.rates.insert(currency.into(), rate);
+        }
+
+        /// Add cash in a currency, or withdraw it with a negative amount.
+        pub fn deposit(&mut self, currency: impl Into<String>, amount: f64) {
+            *self.cash.entry(currency.into()).or_insert(0.0) += amount;
+        }
+
+        /// Returns the currency the Portfolio is valued in.
+        pub fn base_currency(&self) -> &str {
+            &self.base_currency
+        }
+
+        /// Returns the cash held in a currency.
+        pub fn cash(&self, currency: &str) -> f64 {
+            self.cash.get(currency).copied().unwrap_or(0.0)
+        }
+
+        /// Returns the position in an instrument, if it has been traded.
+        pub fn position(&self, symbol: &Symbol) -> Option<&Position> {
+            self.positions.get(symbol)
+        }
+
+        /// Returns every snapshot taken by marking the Portfolio.
+        pub fn snapshots(&self) -> &[Snapshot] {
+            &self.snapshots
+        }
+
+        /// Update the Portfolio with a fill, settling cash and fees in the instrument's
+        /// currency.
+        pub fn apply(&mut self, fill: &Fill) -> Result<(), PortfolioError> {
+            let instrument = self.instrument(&fill.symbol)?;
+            let (currency, multiplier) = (instrument.currency.clone(), instrument.multiplier);
+
+            self.deposit(
+                currency,
+                -fill.signed_quantity() * fill.price * multiplier - fill.fee,
+            );
+            self.positions
+                .entry(fill.symbol.clone())
+                .or_insert_with(|| Position::new(fill.symbol.clone()).with_multiplier(multiplier))
+                .apply(fill);
+
+            Ok(())
+        }
+
+        /// Mark instruments to the close of their latest OHLC, recording and returning a
+        /// snapshot at `ts`.
+        ///
+        /// Nothing is updated if any instrument is unknown or a rate is missing.
+        pub fn mark(
+            &mut self,
+            ts: u64,
+            ohlcs: &[(&Symbol, &OHLC)],
+        ) -> Result<&Snapshot, PortfolioError> {
+            let mut prices = self.prices.clone();
+            for (symbol, ohlc) in ohlcs {
+                self.instrument(symbol)?;
+                prices.insert((*symbol).clone(), ohlc.close);
+            }
+
+            let snapshot = self.value(ts, &prices)?;
+            self.prices = prices;
+            self.snapshots.push(snapshot);
+            Ok(&self.snapshots[self.snapshots.len() - 1])
+        }
+
+        /// Returns a snapshot at `ts` from the latest prices and rates, without recording it.
+        pub fn snapshot(&self, ts: u64) -> Result<Snapshot, PortfolioError> {
+            self.value(ts, &self.prices)
+        }
+
+        fn value(
+            &self,
+            ts: u64,
+            prices: &BTreeMap<Symbol, f64>,
+        ) -> Result<Snapshot, PortfolioError> {
+            let mut cash = 0.0;
+            for (currency, amount) in &self.cash {
+                if *amount != 0.0 {
+                    cash += amount * self.rate(currency)?;
+                }
+            }
+
+            let mut exposures = Vec::new();
+            for (symbol, position) in &self.positions {
+                if position.is_flat() {
+                    continue;
+                }
+                let instrument = self.instrument(symbol)?;
+                let price = prices.get(symbol).copied().unwrap_or(position.avg_price);
+                let value = position.market_value(price) * self.rate(&instrument.currency)?;
+                exposures.push((symbol.clone(), value));
+            }
+
+            let net_exposure: f64 = exposures.iter().map(|(_, value)| value).sum();
+
+            Ok(Snapshot {
+                ts,
+                cash,
+                equity: cash + net_exposure,
+                gross_exposure: exposures.iter().map(|(_, value)| value.abs()).sum(),
+                net_exposure,
+                exposures,
+            })
+        }
+
+        fn instrument(&self, symbol: &Symbol) -> Result<&Instrument, PortfolioError> {
+            self.instruments
+                .get(symbol)
+                .ok_or_else(|| PortfolioError::UnknownInstrument {
+                    symbol: symbol.clone(),
+                })
+        }
+
+        fn rate(&self, currency: &str) -> Result<f64, PortfolioError> {
+            if currency == self.base_currency {
+                return Ok(1.0);
+            }
+            self.rates
+                .get(currency)
+                .copied()
+                .ok_or_else(|| PortfolioError::MissingRate {
+                    currency: currency.to_string(),
+                })
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_instrument_rounding() {
+            let instrument = Instrument::new("ES", "USD")
+                .with_tick_size(0.25)
+                .with_lot_size(1.0)
+                .with_min_quantity(2.0)
+                .with_multiplier(50.0);
+
+            let test_cases = vec![
+                ("round price up", instrument.round_price(4500.13), 4500.25),
+                ("round price down", instrument.round_price(4500.1), 4500.0),
+                ("round quantity down", instrument.round_quantity(3.7), 3.0),
+                ("exact lots", instrument.round_quantity(0.3 / 0.1), 3.0),
+                ("below minimum", instrument.round_quantity(1.9), 0.0),
+                ("negative", instrument.round_quantity(-5.0), 0.0),
+            ];
+
+            for (name, result, expected) in test_cases {
+                assert!(
+                    (result - expected).abs() < 1e-9,
+                    "Test case '{}' failed: expected {}, got {}",
+                    name,
+                    expected,
+                    result
+                );
+            }
+
+            let unconstrained = Instrument::new("BTC", "USD");
+            assert_eq!(unconstrained.round_price(100.123), 100.123);
+            assert_eq!(unconstrained.round_quantity(0.0123), 0.0123);
+        }
+
+        #[test]
+        fn test_order() {
+            let order = Order::stop_limit("AAPL", Side::Buy, 10.0, 101.0, 102.0, 1625097600)
+                .with_id(7)
+                .with_time_in_force(TimeInForce::Gtd(1625097660));
+
+            let expected = Order {
+                id: 7,
+                symbol: Symbol::new("AAPL"),
+                side: Side::Buy,
+                quantity: 10.0,
+                order_type: OrderType::StopLimit {
+                    stop: 101.0,
+                    limit: 102.0,
+                },
+                time_in_force: TimeInForce::Gtd(1625097660),
+                ts: 1625097600,
+            };
+
+            assert_eq!(order, expected, "Order struct should match expected values");
+            assert!(!order.is_expired(1625097660));
+            assert!(order.is_expired(1625097661));
+            assert_eq!(
+                Order::market("AAPL", Side::Sell, 1.0, 1).order_type,
+                OrderType::Market
+            );
+        }
+
+        #[test]
+        fn test_fill() {
+            let ohlcs = vec![
+                OHLC::new(100.0, 110.0, 95.0, 105.0, 1000),
+                OHLC::new(105.0, 112.0, 101.0, 108.0, 2000),
+            ];
+            let order = Order::limit("AAPL", Side::Sell, 5.0, 110.0, 1500).with_id(3);
+
+            let fill = Fill::from_order(&order, 110.0, 5.0, &ohlcs[1]).with_fee(1.5);
+
+            assert_eq!(
+                fill,
+                Fill {
+                    order_id: 3,
+                    symbol: Symbol::new("AAPL"),
+                    side: Side::Sell,
+                    price: 110.0,
+                    quantity: 5.0,
+                    fee: 1.5,
+                    liquidity: Liquidity::Taker,
+                    ts: 2000,
+                }
+            );
+            assert_eq!(fill.signed_quantity(), -5.0);
+            assert_eq!(fill.notional(), 550.0);
+            assert_eq!(fill.bar_index(&ohlcs), Some(1));
+            assert_eq!(
+                Fill::new(1, "AAPL", Side::Buy, 1.0, 1.0, 999).bar_index(&ohlcs),
+                None
+            );
+        }
+
+        #[test]
+        fn test_position_apply() {
+            struct TestCase {
+                name: &'static str,
+                fills: Vec<(Side, f64, f64)>,
+                quantity: f64,
+                avg_price: f64,
+                realized_pnl: f64,
+            }
+
+            let test_cases = vec![
+                TestCase {
+                    name: "open long",
+                    fills: vec![(Side::Buy, 10.0, 100.0)],
+                    quantity: 10.0,
+                    avg_price: 100.0,
+                    realized_pnl: 0.0,
+                },
+                TestCase {
+                    name: "add to long",
+                    fills: vec![(Side::Buy, 10.0, 100.0), (Side::Buy, 30.0, 104.0)],
+                    quantity: 40.0,
+                    avg_price: 103.0,
+                    realized_pnl: 0.0,
+                },
+                TestCase {
+                    name: "reduce long",
+                    fills: vec![(Side::Buy, 10.0, 100.0), (Side::Sell, 4.0, 105.0)],
+                    quantity: 6.0,
+                    avg_price: 100.0,
+                    realized_pnl: 20.0,
+                },
+                TestCase {
+                    name: "close long",
+                    fills: vec![(Side::Buy, 10.0, 100.0), (Side::Sell, 10.0, 95.0)],
+                    quantity: 0.0,
+                    avg_price: 0.0,
+                    realized_pnl: -50.0,
+                },
+                TestCase {
+                    name: "flip long to short",
+                    fills: vec![(Side::Buy, 10.0, 100.0), (Side::Sell, 15.0, 110.0)],
+                    quantity: -5.0,
+                    avg_price: 110.0,
+                    realized_pnl: 100.0,
+                },
+                TestCase {
+                    name: "zero quantity when flat",
+                    fills: vec![(Side::Buy, 0.0, 100.0)],
+                    quantity: 0.0,
+                    avg_price: 0.0,
+                    realized_pnl: 0.0,
+                },
+                TestCase {
+                    name: "zero quantity when long",
+                    fills: vec![(Side::Buy, 10.0, 100.0), (Side::Sell, 0.0, 120.0)],
+                    quantity: 10.0,
+                    avg_price: 100.0,
+                    realized_pnl: 0.0,
+                },
+                TestCase {
+                    name: "cover short",
+                    fills: vec![(Side::Sell, 10.0, 100.0), (Side::Buy, 10.0, 90.0)],
+                    quantity: 0.0,
+                    avg_price: 0.0,
+                    realized_pnl: 100.0,
+                },
+            ];
+
+            for case in test_cases {
+                let mut position = Position::new("AAPL");
+                for (side, quantity, price) in case.fills {
+                    position.apply(&Fill::new(0, "AAPL", side, price, quantity, 1).with_fee(1.0));
+                }
+
+                assert_eq!(position.quantity, case.quantity, "{}: quantity", case.name);
+                assert_eq!(
+                    position.avg_price, case.avg_price,
+                    "{}: avg price",
+                    case.name
+                );
+                assert_eq!(
+                    position.realized_pnl, case.realized_pnl,
+                    "{}: realized P&L",
+                    case.name
+                );
+            }
+        }
+
+        #[test]
+        fn test_position_mark() {
+            let mut position = Position::new("ES").with_multiplier(50.0);
+            position.apply(&Fill::new(0, "ES", Side::Sell, 4500.0, 2.0, 1).with_fee(4.0));
+
+            let ohlc = OHLC::new(4500.0, 4510.0, 4480.0, 4490.0, 2);
+
+            assert_eq!(position.mark(&ohlc), 1000.0);
+            assert_eq!(position.market_value(4490.0), -449_000.0);
+            assert_eq!(position.fees, 4.0);
+            assert!(!position.is_flat());
+        }
+
+        fn portfolio() -> Portfolio {
+            Portfolio::new("USD")
+                .with_instrument(Instrument::new("AAPL", "USD"))
+                .with_instrument(Instrument::new("SAP", "EUR"))
+                .with_cash("USD", 100_000.0)
+                .with_rate("EUR", 1.1)
+        }
+
+        #[test]
+        fn test_portfolio_mark() {
+            let (aapl, sap) = (Symbol::new("AAPL"), Symbol::new("SAP"));
+            let mut portfolio = portfolio();
+            portfolio
+                .apply(&Fill::new(1, "AAPL", Side::Buy, 150.0, 100.0, 1).with_fee(1.0))
+                .unwrap();
+            portfolio
+                .apply(&Fill::new(2, "SAP", Side::Sell, 120.0, 10.0, 1))
+                .unwrap();
+
+            assert_eq!(portfolio.cash("USD"), 84_999.0);
+            assert_eq!(portfolio.cash("EUR"), 1200.0);
+            assert_eq!(portfolio.position(&sap).unwrap().quantity, -10.0);
+
+            let aapl_ohlc = OHLC::new(150.0, 156.0, 149.0, 155.0, 2);
+            let sap_ohlc = OHLC::new(120.0, 126.0, 119.0, 125.0, 2);
+            let snapshot = portfolio
+                .mark(2, &[(&aapl, &aapl_ohlc), (&sap, &sap_ohlc)])
+                .unwrap()
+                .clone();
+
+            let expected = Snapshot {
+                ts: 2,
+                cash: 84_999.0 + 1200.0 * 1.1,
+                equity: 84_999.0 + 1200.0 * 1.1 + 15_500.0 - 1250.0 * 1.1,
+                gross_exposure: 15_500.0 + 1250.0 * 1.1,
+                net_exposure: 15_500.0 - 1250.0 * 1.1,
+                exposures: vec![(aapl.clone(), 15_500.0), (sap.clone(), -1250.0 * 1.1)],
+            };
+            assert_eq!(snapshot, expected);
+            assert!((snapshot.equity - 100_444.0).abs() < 1e-9);
+
+            portfolio.set_rate("EUR", 1.2);
+            let repriced = portfolio.mark(3, &[]).unwrap();
+            assert_eq!(repriced.ts, 3);
+            assert!((repriced.equity - (84_999.0 + 1440.0 + 15_500.0 - 1500.0)).abs() < 1e-9);
+            assert_eq!(portfolio.snapshots().len(), 2);
+        }
+
+        #[test]
+        fn test_portfolio_unmarked() {
+            let mut portfolio = portfolio();
+            portfolio
+                .apply(&Fill::new(1, "AAPL", Side::Buy, 150.0, 10.0, 1))
+                .unwrap();
+
+            let snapshot = portfolio.snapshot(1).unwrap();
+
+            assert_eq!(snapshot.equity, 100_000.0);
+            assert_eq!(snapshot.exposures, vec![(Symbol::new("AAPL"), 1500.0)]);
+            assert!(portfolio.snapshots().is_empty());
+        }
+
+        #[test]
+        fn test_portfolio_errors() {
+            let mut portfolio = portfolio().with_instrument(Instrument::new("7203", "JPY"));
+            let unknown = Symbol::new("MSFT");
+            let ohlc = OHLC::new(1.0, 1.0, 1.0, 1.0, 1);
+
+            assert_eq!(
+                portfolio.apply(&Fill::new(1, "MSFT", Side::Buy, 300.0, 1.0, 1)),
+                Err(PortfolioError::UnknownInstrument {
+                    symbol: unknown.clone()
+                })
+            );
+            assert_eq!(
+                portfolio.mark(1, &[(&unknown, &ohlc)]).cloned(),
+                Err(PortfolioError::UnknownInstrument { symbol: unknown })
+            );
+            assert_eq!(portfolio.cash("USD"), 100_000.0);
+
+            portfolio
+                .apply(&Fill::new(1, "7203", Side::Buy, 2500.0, 100.0, 1))
+                .unwrap();
+            assert_eq!(
+                portfolio.snapshot(1),
+                Err(PortfolioError::MissingRate {
+                    currency: "JPY".to_string()
+                })
+            );
+
+            let aapl = Symbol::new("AAPL");
+            let before = portfolio.clone();
+            assert_eq!(
+                portfolio
+                    .mark(2, &[(&aapl, &OHLC::new(1.0, 1.0, 1.0, 1.0, 2))])
+                    .cloned(),
+                Err(PortfolioError::MissingRate {
+                    currency: "JPY".to_string()
+                })
+            );
+            assert_eq!(portfolio, before, "A failed mark should change nothing");
+            assert_eq!(
+                PortfolioError::MissingRate {
+                    currency: "JPY".to_string()
+                }
+                .to_string(),
+                "No exchange rate for JPY"
+            );
+        }
+
+        #[test]
+        fn test_sizing() {
+            let instrument = Instrument::new("ES", "USD")
+                .with_lot_size(1.0)
+                .with_min_quantity(1.0)
+                .with_multiplier(50.0);
+            let input = SizingInput::new(1_000_000.0, 4000.0)
+                .with_stop_distance(20.0)
+                .with_volatility(0.16);
+            let kelly = Sizing::Kelly {
+                fraction: 0.5,
+                win_rate: 0.55,
+                payoff: 1.5,
+            };
+
+            let test_cases = vec![
+                ("fixed", Sizing::Fixed(3.5), input, 3.0),
+                ("fixed fractional", Sizing::FixedFractional(0.5), input, 2.0),
+                ("fixed risk", Sizing::FixedRisk(0.01), input, 10.0),
+                (
+                    "volatility target",
+                    Sizing::VolatilityTarget(0.1),
+                    input,
+                    3.0,
+                ),
+                // Kelly of 0.55 - 0.45 / 1.5 = 0.25, halved, is 12.5% of equity.
+                ("kelly", kelly, input, 0.0),
+                (
+                    "kelly larger account",
+                    kelly,
+                    SizingInput::new(10_000_000.0, 4000.0),
+                    6.0,
+                ),
+                ("below minimum", Sizing::FixedFractional(0.1), input, 0.0),
+                (
+                    "missing stop",
+                    Sizing::FixedRisk(0.01),
+                    SizingInput::new(1_000_000.0, 4000.0),
+                    0.0,
+                ),
+                (
+                    "zero volatility",
+                    Sizing::VolatilityTarget(0.1),
+                    input.with_volatility(0.0),
+                    0.0,
+                ),
+                (
+                    "negative kelly",
+                    Sizing::Kelly {
+                        fraction: 1.0,
+                        win_rate: 0.3,
+                        payoff: 1.0,
+                    },
+                    input,
+                    0.0,
+                ),
+            ];
+
+            for (name, sizing, input, expected) in test_cases {
+                let result = sizing.quantity(&instrument, &input);
+
+                assert_eq!(result, expected, "Test case '{}' failed", name);
+            }
+        }
+
+        #[test]
+        fn test_sizing_order() {
+            let instrument = Instrument::new("AAPL", "USD").with_lot_size(1.0);
+            let input = SizingInput::new(10_000.0, 150.0);
+
+            let order = Sizing::FixedFractional(0.5).order(&instrument, Side::Buy, &input, 7);
+
+            assert_eq!(order, Some(Order::market("AAPL", Side::Buy, 33.0, 7)));
+            assert_eq!(
+                Sizing::Fixed(0.5).order(&instrument, Side::Sell, &input, 7),
+                None
+            );
+        }
+
+        #[test]
+        fn test_atr_stop_distance() {
+            let ohlcs = vec![
+                OHLC::new(10.0, 11.0, 9.0, 10.0, 1),
+                OHLC::new(10.0, 12.0, 10.0, 11.0, 2),
+                OHLC::new(11.0, 12.0, 8.0, 9.0, 3),
+            ];
+
+            let result = atr_stop_distance(&ohlcs, 2, 1.5);
+
+            assert_eq!(result, atr(&ohlcs, 2)[2].map(|atr| atr * 1.5));
+            assert!(result.is_some());
+            assert_eq!(atr_stop_distance(&ohlcs[..1], 2, 1.5), None);
+        }
+    }
 }
@@ -1,8 +1,12 @@
 pub mod ohlc {
+    use crate::timestamp::timestamp::{Timestamp, Unit};
+    use std::fmt;
+
     const BULLISH: i8 = 1;
     const BEARISH: i8 = -1;
 
     #[derive(Debug, PartialEq, Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct OHLC {
         pub open: f64,
         pub high: f64,
@@ -12,6 +16,64 @@ pub mod ohlc {
         pub ts: u64,
     }
 
+    /// A price or volume field of an OHLC.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum Field {
+        Open,
+        High,
+        Low,
+        Close,
+        Volume,
+    }
+
+    impl fmt::Display for Field {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Field::Open => write!(f, "Open price"),
+                Field::High => write!(f, "High price"),
+                Field::Low => write!(f, "Low price"),
+                Field::Close => write!(f, "Close price"),
+                Field::Volume => write!(f, "Volume"),
+            }
+        }
+    }
+
+    /// A reason an OHLC failed validation.
+    #[derive(Debug, PartialEq, Eq, Clone)]
+    pub enum OhlcError {
+        NonFiniteField { field: Field },
+        HighBelowLow,
+        OpenOutsideRange,
+        CloseOutsideRange,
+        NegativeVolume,
+        ZeroTimestamp,
+        ImplausibleTimestamp { unit: Unit },
+    }
+
+    impl fmt::Display for OhlcError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                OhlcError::NonFiniteField { field } => write!(f, "{} must be finite", field),
+                OhlcError::HighBelowLow => {
+                    write!(f, "High price must be greater than or equal to low price")
+                }
+                OhlcError::OpenOutsideRange => {
+                    write!(f, "Open price must be between the low and high prices")
+                }
+                OhlcError::CloseOutsideRange => {
+                    write!(f, "Close price must be between the low and high prices")
+                }
+                OhlcError::NegativeVolume => write!(f, "Volume must be non-negative"),
+                OhlcError::ZeroTimestamp => write!(f, "Timestamp must be non-zero"),
+                OhlcError::ImplausibleTimestamp { unit } => {
+                    write!(f, "Timestamp is not a plausible time in {}", unit)
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for OhlcError {}
+
     /// All opens from a slice of OHLC.
     pub fn opens(ohlcs: &[OHLC]) -> Vec<f64> {
         ohlcs.iter().map(|ohlc| ohlc.open).collect()
@@ -34,17 +96,22 @@ pub mod ohlc {
 
     /// Options for filtering OHLC slices.
     pub struct Opts {
-        pub exclude_before: Option<u64>,
-        pub exclude_after: Option<u64>,
+        pub exclude_before: Option<Timestamp>,
+        pub exclude_after: Option<Timestamp>,
+        /// The unit of the OHLC timestamps.
+        pub unit: Unit,
     }
 
     /// Filters a slice of OHLC returning a Vector of OHLC matching the supplied options.
+    ///
+    /// Scans every OHLC. Use `OhlcSeries::filter` for a binary search over sorted OHLC.
     pub fn filter(ohlcs: &[OHLC], opts: Opts) -> Vec<OHLC> {
         ohlcs
             .iter()
             .filter(|ohlc| {
-                let before_ok = opts.exclude_before.map_or(true, |before| ohlc.ts >= before);
-                let after_ok = opts.exclude_after.map_or(true, |after| ohlc.ts <= after);
+                let ts = ohlc.timestamp(opts.unit);
+                let before_ok = opts.exclude_before.is_none_or(|before| ts >= before);
+                let after_ok = opts.exclude_after.is_none_or(|after| ts <= after);
                 before_ok && after_ok
             })
             .cloned()
@@ -71,7 +138,7 @@ pub mod ohlc {
             low: f64,
             close: f64,
             ts: u64,
-        ) -> Result<Self, Vec<String>> {
+        ) -> Result<Self, Vec<OhlcError>> {
             let ohlc = Self::new(open, high, low, close, ts);
             ohlc.validate()?;
             Ok(ohlc)
@@ -84,34 +151,47 @@ pub mod ohlc {
         }
 
         /// Validate an OHLC.
-        pub fn validate(&self) -> Result<(), Vec<String>> {
+        ///
+        /// The open and close are only checked against the range when all prices are finite and
+        /// the high is not below the low.
+        pub fn validate(&self) -> Result<(), Vec<OhlcError>> {
             let mut errors = Vec::new();
 
-            if !self.open.is_finite() {
-                errors.push("Open price must be finite".to_string());
-            }
-            if !self.high.is_finite() {
-                errors.push("High price must be finite".to_string());
-            }
-            if !self.low.is_finite() {
-                errors.push("Low price must be finite".to_string());
-            }
-            if !self.close.is_finite() {
-                errors.push("Close price must be finite".to_string());
+            let prices = [
+                (Field::Open, self.open),
+                (Field::High, self.high),
+                (Field::Low, self.low),
+                (Field::Close, self.close),
+            ];
+            for (field, value) in prices {
+                if !value.is_finite() {
+                    errors.push(OhlcError::NonFiniteField { field });
+                }
             }
 
             if self.high < self.low {
-                errors.push("High price must be greater than or equal to low price".to_string());
+                errors.push(OhlcError::HighBelowLow);
+            } else if errors.is_empty() {
+                if self.open < self.low || self.open > self.high {
+                    errors.push(OhlcError::OpenOutsideRange);
+                }
+                if self.close < self.low || self.close > self.high {
+                    errors.push(OhlcError::CloseOutsideRange);
+                }
             }
 
             if let Some(vol) = self.vol {
-                if !vol.is_finite() || vol < 0.0 {
-                    errors.push("Volume must be non-negative and finite".to_string());
+                if !vol.is_finite() {
+                    errors.push(OhlcError::NonFiniteField {
+                        field: Field::Volume,
+                    });
+                } else if vol < 0.0 {
+                    errors.push(OhlcError::NegativeVolume);
                 }
             }
 
             if self.ts == 0 {
-                errors.push("Timestamp must be non-zero".to_string());
+                errors.push(OhlcError::ZeroTimestamp);
             }
 
             if errors.is_empty() {
@@ -121,6 +201,28 @@ pub mod ohlc {
             }
         }
 
+        /// Validate an OHLC, also checking the timestamp is plausible in the given unit.
+        ///
+        /// Catches timestamps in the wrong unit, such as seconds where milliseconds are expected.
+        pub fn validate_unit(&self, unit: Unit) -> Result<(), Vec<OhlcError>> {
+            let mut errors = self.validate().err().unwrap_or_default();
+
+            if self.ts != 0 && !self.timestamp(unit).is_plausible() {
+                errors.push(OhlcError::ImplausibleTimestamp { unit });
+            }
+
+            if errors.is_empty() {
+                Ok(())
+            } else {
+                Err(errors)
+            }
+        }
+
+        /// Returns the timestamp, read in the given unit.
+        pub fn timestamp(&self, unit: Unit) -> Timestamp {
+            Timestamp::new(self.ts, unit)
+        }
+
         /// Returns the direction of the OHLC.
         ///
         /// Will return a zero if the open and close values are equal.
@@ -133,6 +235,38 @@ pub mod ohlc {
                 0
             }
         }
+
+        /// Returns the size of the body, the distance between the open and close.
+        pub fn body(&self) -> f64 {
+            (self.close - self.open).abs()
+        }
+
+        /// Returns the distance from the top of the body to the high.
+        pub fn upper_shadow(&self) -> f64 {
+            self.high - self.open.max(self.close)
+        }
+
+        /// Returns the distance from the bottom of the body to the low.
+        pub fn lower_shadow(&self) -> f64 {
+            self.open.min(self.close) - self.low
+        }
+
+        /// Returns the distance from the low to the high.
+        pub fn range(&self) -> f64 {
+            self.high - self.low
+        }
+
+        /// Returns the body as a fraction of the range.
+        ///
+        /// Will return a zero if the range is zero.
+        pub fn body_ratio(&self) -> f64 {
+            let range = self.range();
+            if range == 0.0 {
+                0.0
+            } else {
+                self.body() / range
+            }
+        }
     }
 
     #[cfg(test)]
@@ -299,8 +433,9 @@ pub mod ohlc {
                         fake_ohlc(4000),
                     ],
                     Opts {
-                        exclude_before: Some(1500),
-                        exclude_after: Some(3500),
+                        exclude_before: Some(Timestamp::from_secs(1500)),
+                        exclude_after: Some(Timestamp::from_secs(3500)),
+                        unit: Unit::Seconds,
                     },
                     vec![fake_ohlc(2000), fake_ohlc(3000)],
                 ),
@@ -313,8 +448,9 @@ pub mod ohlc {
                         fake_ohlc(4000),
                     ],
                     Opts {
-                        exclude_before: Some(2000),
+                        exclude_before: Some(Timestamp::from_secs(2000)),
                         exclude_after: None,
+                        unit: Unit::Seconds,
                     },
                     vec![fake_ohlc(2000), fake_ohlc(3000), fake_ohlc(4000)],
                 ),
@@ -328,7 +464,8 @@ pub mod ohlc {
                     ],
                     Opts {
                         exclude_before: None,
-                        exclude_after: Some(2500),
+                        exclude_after: Some(Timestamp::from_secs(2500)),
+                        unit: Unit::Seconds,
                     },
                     vec![fake_ohlc(1000), fake_ohlc(2000)],
                 ),
@@ -343,6 +480,7 @@ pub mod ohlc {
                     Opts {
                         exclude_before: None,
                         exclude_after: None,
+                        unit: Unit::Seconds,
                     },
                     vec![
                         fake_ohlc(1000),
@@ -360,8 +498,9 @@ pub mod ohlc {
                         fake_ohlc(4000),
                     ],
                     Opts {
-                        exclude_before: Some(0),
-                        exclude_after: Some(3500),
+                        exclude_before: Some(Timestamp::from_secs(0)),
+                        exclude_after: Some(Timestamp::from_secs(3500)),
+                        unit: Unit::Seconds,
                     },
                     vec![fake_ohlc(1000), fake_ohlc(2000), fake_ohlc(3000)],
                 ),
@@ -374,8 +513,9 @@ pub mod ohlc {
                         fake_ohlc(4000),
                     ],
                     Opts {
-                        exclude_before: Some(1500),
-                        exclude_after: Some(0),
+                        exclude_before: Some(Timestamp::from_secs(1500)),
+                        exclude_after: Some(Timestamp::from_secs(0)),
+                        unit: Unit::Seconds,
                     },
                     vec![],
                 ),
@@ -383,8 +523,9 @@ pub mod ohlc {
                     "empty_input",
                     vec![],
                     Opts {
-                        exclude_before: Some(1500),
-                        exclude_after: Some(3500),
+                        exclude_before: Some(Timestamp::from_secs(1500)),
+                        exclude_after: Some(Timestamp::from_secs(3500)),
+                        unit: Unit::Seconds,
                     },
                     vec![],
                 ),
@@ -392,8 +533,9 @@ pub mod ohlc {
                     "single_element_within_bounds",
                     vec![fake_ohlc(2000)],
                     Opts {
-                        exclude_before: Some(1500),
-                        exclude_after: Some(2500),
+                        exclude_before: Some(Timestamp::from_secs(1500)),
+                        exclude_after: Some(Timestamp::from_secs(2500)),
+                        unit: Unit::Seconds,
                     },
                     vec![fake_ohlc(2000)],
                 ),
@@ -401,11 +543,27 @@ pub mod ohlc {
                     "single_element_outside_bounds",
                     vec![fake_ohlc(1000)],
                     Opts {
-                        exclude_before: Some(1500),
-                        exclude_after: Some(2500),
+                        exclude_before: Some(Timestamp::from_secs(1500)),
+                        exclude_after: Some(Timestamp::from_secs(2500)),
+                        unit: Unit::Seconds,
                     },
                     vec![],
                 ),
+                (
+                    "millisecond_bounds",
+                    vec![
+                        fake_ohlc(1000),
+                        fake_ohlc(2000),
+                        fake_ohlc(3000),
+                        fake_ohlc(4000),
+                    ],
+                    Opts {
+                        exclude_before: Some(Timestamp::from_millis(1000001)),
+                        exclude_after: Some(Timestamp::from_millis(3000000)),
+                        unit: Unit::Seconds,
+                    },
+                    vec![fake_ohlc(2000), fake_ohlc(3000)],
+                ),
             ];
 
             for (name, input, opts, expected) in test_cases {
@@ -561,6 +719,72 @@ pub mod ohlc {
             }
         }
 
+        #[test]
+        fn test_geometry() {
+            struct TestCase {
+                name: &'static str,
+                ohlc: OHLC,
+                body: f64,
+                upper_shadow: f64,
+                lower_shadow: f64,
+                range: f64,
+                body_ratio: f64,
+            }
+
+            let test_cases = vec![
+                TestCase {
+                    name: "bullish",
+                    ohlc: OHLC::new(100.0, 110.0, 95.0, 105.0, 1625097600),
+                    body: 5.0,
+                    upper_shadow: 5.0,
+                    lower_shadow: 5.0,
+                    range: 15.0,
+                    body_ratio: 5.0 / 15.0,
+                },
+                TestCase {
+                    name: "bearish",
+                    ohlc: OHLC::new(108.0, 110.0, 90.0, 100.0, 1625097600),
+                    body: 8.0,
+                    upper_shadow: 2.0,
+                    lower_shadow: 10.0,
+                    range: 20.0,
+                    body_ratio: 0.4,
+                },
+                TestCase {
+                    name: "flat",
+                    ohlc: OHLC::new(100.0, 100.0, 100.0, 100.0, 1625097600),
+                    body: 0.0,
+                    upper_shadow: 0.0,
+                    lower_shadow: 0.0,
+                    range: 0.0,
+                    body_ratio: 0.0,
+                },
+            ];
+
+            for case in test_cases {
+                assert_eq!(case.ohlc.body(), case.body, "{}: body", case.name);
+                assert_eq!(
+                    case.ohlc.upper_shadow(),
+                    case.upper_shadow,
+                    "{}: upper shadow",
+                    case.name
+                );
+                assert_eq!(
+                    case.ohlc.lower_shadow(),
+                    case.lower_shadow,
+                    "{}: lower shadow",
+                    case.name
+                );
+                assert_eq!(case.ohlc.range(), case.range, "{}: range", case.name);
+                assert_eq!(
+                    case.ohlc.body_ratio(),
+                    case.body_ratio,
+                    "{}: body ratio",
+                    case.name
+                );
+            }
+        }
+
         #[test]
         fn test_build() {
             struct TestCase {
@@ -571,7 +795,7 @@ pub mod ohlc {
                 close: f64,
                 vol: Option<f64>,
                 ts: u64,
-                expected: Result<OHLC, Vec<String>>,
+                expected: Result<OHLC, Vec<OhlcError>>,
             }
 
             let test_cases = vec![
@@ -617,9 +841,7 @@ pub mod ohlc {
                     close: 105.0,
                     vol: None,
                     ts: 1625097600000,
-                    expected: Err(vec![
-                        "High price must be greater than or equal to low price".to_string(),
-                    ]),
+                    expected: Err(vec![OhlcError::HighBelowLow]),
                 },
                 TestCase {
                     name: "invalid negative volume",
@@ -629,7 +851,7 @@ pub mod ohlc {
                     close: 105.0,
                     vol: Some(-1000.0),
                     ts: 1625097600000,
-                    expected: Err(vec!["Volume must be non-negative and finite".to_string()]),
+                    expected: Err(vec![OhlcError::NegativeVolume]),
                 },
                 TestCase {
                     name: "invalid zero timestamp",
@@ -639,7 +861,7 @@ pub mod ohlc {
                     close: 105.0,
                     vol: None,
                     ts: 0,
-                    expected: Err(vec!["Timestamp must be non-zero".to_string()]),
+                    expected: Err(vec![OhlcError::ZeroTimestamp]),
                 },
                 TestCase {
                     name: "multiple invalid fields",
@@ -650,13 +872,50 @@ pub mod ohlc {
                     vol: Some(-1000.0),
                     ts: 0,
                     expected: Err(vec![
-                        "Open price must be finite".to_string(),
-                        "Close price must be finite".to_string(),
-                        "High price must be greater than or equal to low price".to_string(),
-                        "Volume must be non-negative and finite".to_string(),
-                        "Timestamp must be non-zero".to_string(),
+                        OhlcError::NonFiniteField { field: Field::Open },
+                        OhlcError::NonFiniteField {
+                            field: Field::Close,
+                        },
+                        OhlcError::HighBelowLow,
+                        OhlcError::NegativeVolume,
+                        OhlcError::ZeroTimestamp,
                     ]),
                 },
+                TestCase {
+                    name: "invalid open above high",
+                    open: 111.0,
+                    high: 110.0,
+                    low: 95.0,
+                    close: 105.0,
+                    vol: None,
+                    ts: 1625097600000,
+                    expected: Err(vec![OhlcError::OpenOutsideRange]),
+                },
+                TestCase {
+                    name: "invalid open and close below low",
+                    open: 94.0,
+                    high: 110.0,
+                    low: 95.0,
+                    close: 90.0,
+                    vol: None,
+                    ts: 1625097600000,
+                    expected: Err(vec![
+                        OhlcError::OpenOutsideRange,
+                        OhlcError::CloseOutsideRange,
+                    ]),
+                },
+                TestCase {
+                    name: "invalid infinite volume",
+                    open: 100.0,
+                    high: 110.0,
+                    low: 95.0,
+                    close: 105.0,
+                    vol: Some(f64::INFINITY),
+                    ts: 1625097600000,
+                    expected: Err(vec![OhlcError::NonFiniteField {
+                        field: Field::Volume,
+                    }]),
+                },
             ];
 
             for case in test_cases {
@@ -700,5 +959,102 @@ pub mod ohlc {
                 }
             }
         }
+
+        #[test]
+        fn test_validate_unit() {
+            let test_cases = vec![
+                ("seconds", 1625097600, Unit::Seconds, Ok(())),
+                ("millis", 1625097600000, Unit::Millis, Ok(())),
+                (
+                    "seconds read as millis",
+                    1625097600,
+                    Unit::Millis,
+                    Err(vec![OhlcError::ImplausibleTimestamp { unit: Unit::Millis }]),
+                ),
+                (
+                    "millis read as seconds",
+                    1625097600000,
+                    Unit::Seconds,
+                    Err(vec![OhlcError::ImplausibleTimestamp {
+                        unit: Unit::Seconds,
+                    }]),
+                ),
+                (
+                    "zero",
+                    0,
+                    Unit::Seconds,
+                    Err(vec![OhlcError::ZeroTimestamp]),
+                ),
+            ];
+
+            for (name, ts, unit, expected) in test_cases {
+                let ohlc = OHLC::new(100.0, 110.0, 95.0, 105.0, ts);
+
+                assert_eq!(
+                    ohlc.validate_unit(unit),
+                    expected,
+                    "Test case '{}' failed",
+                    name
+                );
+            }
+        }
+
+        #[test]
+        fn test_ohlc_error_display() {
+            let test_cases = vec![
+                (
+                    OhlcError::NonFiniteField { field: Field::Open },
+                    "Open price must be finite",
+                ),
+                (
+                    OhlcError::NonFiniteField {
+                        field: Field::Volume,
+                    },
+                    "Volume must be finite",
+                ),
+                (
+                    OhlcError::HighBelowLow,
+                    "High price must be greater than or equal to low price",
+                ),
+                (
+                    OhlcError::CloseOutsideRange,
+                    "Close price must be between the low and high prices",
+                ),
+                (OhlcError::ZeroTimestamp, "Timestamp must be non-zero"),
+                (
+                    OhlcError::ImplausibleTimestamp { unit: Unit::Millis },
+                    "Timestamp is not a plausible time in milliseconds",
+                ),
+            ];
+
+            for (error, expected) in test_cases {
+                assert_eq!(
+                    error.to_string(),
+                    expected,
+                    "Display failed for {:?}",
+                    error
+                );
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn test_serde() {
+            let ohlc = OHLC::new(100.5, 102.0, 99.25, 101.0, 1625097600).with_volume(1500.0);
+
+            let json = serde_json::to_string(&ohlc).unwrap();
+
+            assert_eq!(
+                json,
+                r#"{"open":100.5,"high":102.0,"low":99.25,"close":101.0,"vol":1500.0,"ts":1625097600}"#
+            );
+            assert_eq!(serde_json::from_str::<OHLC>(&json).unwrap(), ohlc);
+
+            let without_volume: OHLC = serde_json::from_str(
+                r#"{"open":1.0,"high":2.0,"low":0.5,"close":1.5,"vol":null,"ts":60}"#,
+            )
+            .unwrap();
+            assert_eq!(without_volume, OHLC::new(1.0, 2.0, 0.5, 1.5, 60));
+        }
     }
 }
@@ -0,0 +1,375 @@
+pub mod patterns {
+    use crate::ohlc::ohlc::OHLC;
+
+    /// A candlestick pattern.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum Pattern {
+        Doji,
+        Hammer,
+        ShootingStar,
+        BullishEngulfing,
+        BearishEngulfing,
+        BullishHarami,
+        BearishHarami,
+        MorningStar,
+        EveningStar,
+        ThreeWhiteSoldiers,
+        InsideBar,
+        OutsideBar,
+    }
+
+    impl Pattern {
+        /// The number of OHLC that make up the pattern.
+        pub fn bar_count(&self) -> usize {
+            match self {
+                Pattern::Doji | Pattern::Hammer | Pattern::ShootingStar => 1,
+                Pattern::MorningStar | Pattern::EveningStar | Pattern::ThreeWhiteSoldiers => 3,
+                _ => 2,
+            }
+        }
+    }
+
+    /// A pattern found in a slice of OHLC.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub struct Match {
+        /// The index of the last OHLC in the pattern.
+        pub index: usize,
+        pub pattern: Pattern,
+    }
+
+    /// How strictly patterns are matched.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct Tolerance {
+        /// The largest body, as a fraction of the range, for a doji.
+        pub doji_body: f64,
+        /// The smallest long shadow, as a multiple of the body, for a hammer or shooting star.
+        pub shadow_multiple: f64,
+        /// The largest short shadow, as a fraction of the range, for a hammer or shooting star.
+        pub small_shadow: f64,
+        /// The smallest body, as a fraction of the range, for the long candles in multi-OHLC
+        /// patterns.
+        pub long_body: f64,
+        /// The largest middle body of a star, as a fraction of the first body.
+        pub star_body: f64,
+    }
+
+    impl Default for Tolerance {
+        fn default() -> Self {
+            Tolerance {
+                doji_body: 0.1,
+                shadow_multiple: 2.0,
+                small_shadow: 0.1,
+                long_body: 0.5,
+                star_body: 0.3,
+            }
+        }
+    }
+
+    /// Finds every pattern in a slice of OHLC, ordered by index.
+    ///
+    /// Trend context is not considered, so a hammer is reported wherever the shape appears. An
+    /// OHLC can be part of several patterns.
+    pub fn detect(ohlcs: &[OHLC], tolerance: &Tolerance) -> Vec<Match> {
+        let mut matches = Vec::new();
+
+        for index in 0..ohlcs.len() {
+            let found = patterns_at(ohlcs, index, tolerance);
+            matches.extend(found.into_iter().map(|pattern| Match { index, pattern }));
+        }
+
+        matches
+    }
+
+    /// Returns the patterns ending at an index.
+    pub fn patterns_at(ohlcs: &[OHLC], index: usize, tolerance: &Tolerance) -> Vec<Pattern> {
+        let mut found = Vec::new();
+        let Some(c) = ohlcs.get(index) else {
+            return found;
+        };
+
+        if is_doji(c, tolerance) {
+            found.push(Pattern::Doji);
+        }
+        if is_hammer(c, tolerance) {
+            found.push(Pattern::Hammer);
+        }
+        if is_shooting_star(c, tolerance) {
+            found.push(Pattern::ShootingStar);
+        }
+
+        if index >= 1 {
+            let b = &ohlcs[index - 1];
+
+            if bearish(b)
+                && bullish(c)
+                && c.open <= b.close
+                && c.close >= b.open
+                && c.body() > b.body()
+            {
+                found.push(Pattern::BullishEngulfing);
+            }
+            if bullish(b)
+                && bearish(c)
+                && c.open >= b.close
+                && c.close <= b.open
+                && c.body() > b.body()
+            {
+                found.push(Pattern::BearishEngulfing);
+            }
+            if bearish(b)
+                && bullish(c)
+                && c.open >= b.close
+                && c.close <= b.open
+                && c.body() < b.body()
+            {
+                found.push(Pattern::BullishHarami);
+            }
+            if bullish(b)
+                && bearish(c)
+                && c.open <= b.close
+                && c.close >= b.open
+                && c.body() < b.body()
+            {
+                found.push(Pattern::BearishHarami);
+            }
+            if c.high < b.high && c.low > b.low {
+                found.push(Pattern::InsideBar);
+            }
+            if c.high > b.high && c.low < b.low {
+                found.push(Pattern::OutsideBar);
+            }
+        }
+
+        if index >= 2 {
+            let a = &ohlcs[index - 2];
+            let b = &ohlcs[index - 1];
+            let midpoint = (a.open + a.close) / 2.0;
+            let small_star = b.body() <= tolerance.star_body * a.body();
+
+            if bearish(a) && is_long(a, tolerance) && small_star && bullish(c) && c.close > midpoint
+            {
+                found.push(Pattern::MorningStar);
+            }
+            if bullish(a) && is_long(a, tolerance) && small_star && bearish(c) && c.close < midpoint
+            {
+                found.push(Pattern::EveningStar);
+            }
+            if is_soldier(b, a, tolerance) && is_soldier(c, b, tolerance) && bullish(a) {
+                found.push(Pattern::ThreeWhiteSoldiers);
+            }
+        }
+
+        found
+    }
+
+    fn bullish(ohlc: &OHLC) -> bool {
+        ohlc.direction() > 0
+    }
+
+    fn bearish(ohlc: &OHLC) -> bool {
+        ohlc.direction() < 0
+    }
+
+    fn is_long(ohlc: &OHLC, tolerance: &Tolerance) -> bool {
+        ohlc.body_ratio() >= tolerance.long_body
+    }
+
+    fn is_doji(ohlc: &OHLC, tolerance: &Tolerance) -> bool {
+        ohlc.body() <= tolerance.doji_body * ohlc.range()
+    }
+
+    fn is_hammer(ohlc: &OHLC, tolerance: &Tolerance) -> bool {
+        ohlc.lower_shadow() > 0.0
+            && ohlc.lower_shadow() >= tolerance.shadow_multiple * ohlc.body()
+            && ohlc.upper_shadow() <= tolerance.small_shadow * ohlc.range()
+    }
+
+    fn is_shooting_star(ohlc: &OHLC, tolerance: &Tolerance) -> bool {
+        ohlc.upper_shadow() > 0.0
+            && ohlc.upper_shadow() >= tolerance.shadow_multiple * ohlc.body()
+            && ohlc.lower_shadow() <= tolerance.small_shadow * ohlc.range()
+    }
+
+    /// A long bullish candle opening within the previous body and closing higher.
+    fn is_soldier(ohlc: &OHLC, prev: &OHLC, tolerance: &Tolerance) -> bool {
+        bullish(ohlc)
+            && is_long(ohlc, tolerance)
+            && ohlc.open >= prev.open
+            && ohlc.open <= prev.close
+            && ohlc.close > prev.close
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn bar(open: f64, high: f64, low: f64, close: f64) -> OHLC {
+            OHLC::new(open, high, low, close, 1625097600)
+        }
+
+        #[test]
+        fn test_single_patterns() {
+            let test_cases = vec![
+                ("doji", bar(100.0, 105.0, 95.0, 100.5), vec![Pattern::Doji]),
+                (
+                    "hammer",
+                    bar(100.0, 102.2, 90.0, 102.0),
+                    vec![Pattern::Hammer],
+                ),
+                (
+                    "dragonfly doji",
+                    bar(100.0, 100.0, 90.0, 100.0),
+                    vec![Pattern::Doji, Pattern::Hammer],
+                ),
+                (
+                    "shooting star",
+                    bar(102.0, 112.0, 99.9, 100.0),
+                    vec![Pattern::ShootingStar],
+                ),
+                ("plain", bar(100.0, 106.0, 99.0, 105.0), vec![]),
+            ];
+
+            for (name, ohlc, expected) in test_cases {
+                let result = patterns_at(&[ohlc], 0, &Tolerance::default());
+
+                assert_eq!(result, expected, "Test case '{}' failed", name);
+            }
+        }
+
+        #[test]
+        fn test_two_bar_patterns() {
+            let test_cases = vec![
+                (
+                    "bullish engulfing",
+                    vec![
+                        bar(104.0, 105.0, 99.0, 100.0),
+                        bar(99.0, 107.0, 98.0, 106.0),
+                    ],
+                    vec![Pattern::BullishEngulfing, Pattern::OutsideBar],
+                ),
+                (
+                    "bearish engulfing",
+                    vec![
+                        bar(100.0, 104.5, 99.5, 104.0),
+                        bar(105.0, 105.5, 98.0, 99.0),
+                    ],
+                    vec![Pattern::BearishEngulfing, Pattern::OutsideBar],
+                ),
+                (
+                    "bullish harami",
+                    vec![
+                        bar(110.0, 111.0, 99.0, 100.0),
+                        bar(102.0, 106.0, 101.0, 105.0),
+                    ],
+                    vec![Pattern::BullishHarami, Pattern::InsideBar],
+                ),
+                (
+                    "bearish harami",
+                    vec![
+                        bar(100.0, 111.0, 99.0, 110.0),
+                        bar(108.0, 109.0, 103.0, 104.0),
+                    ],
+                    vec![Pattern::BearishHarami, Pattern::InsideBar],
+                ),
+            ];
+
+            for (name, ohlcs, expected) in test_cases {
+                let result = patterns_at(&ohlcs, 1, &Tolerance::default());
+
+                assert_eq!(result, expected, "Test case '{}' failed", name);
+            }
+        }
+
+        #[test]
+        fn test_three_bar_patterns() {
+            let test_cases = vec![
+                (
+                    "morning star",
+                    vec![
+                        bar(110.0, 111.0, 99.0, 100.0),
+                        bar(98.0, 99.5, 96.0, 99.0),
+                        bar(100.0, 108.5, 99.5, 108.0),
+                    ],
+                    vec![Pattern::MorningStar],
+                ),
+                (
+                    "evening star",
+                    vec![
+                        bar(100.0, 111.0, 99.0, 110.0),
+                        bar(112.0, 114.0, 110.5, 111.0),
+                        bar(109.0, 109.5, 101.0, 102.0),
+                    ],
+                    vec![Pattern::EveningStar],
+                ),
+                (
+                    "three white soldiers",
+                    vec![
+                        bar(100.0, 105.5, 99.5, 105.0),
+                        bar(103.0, 108.5, 102.5, 108.0),
+                        bar(106.0, 111.5, 105.5, 111.0),
+                    ],
+                    vec![Pattern::ThreeWhiteSoldiers],
+                ),
+            ];
+
+            for (name, ohlcs, expected) in test_cases {
+                let result = patterns_at(&ohlcs, 2, &Tolerance::default());
+
+                assert_eq!(result, expected, "Test case '{}' failed", name);
+            }
+        }
+
+        #[test]
+        fn test_detect() {
+            let ohlcs = vec![
+                bar(104.0, 105.0, 99.0, 100.0),
+                bar(99.0, 107.0, 98.0, 106.0),
+                bar(106.0, 106.5, 100.0, 106.2),
+            ];
+
+            let result = detect(&ohlcs, &Tolerance::default());
+
+            assert_eq!(
+                result,
+                vec![
+                    Match {
+                        index: 1,
+                        pattern: Pattern::BullishEngulfing
+                    },
+                    Match {
+                        index: 1,
+                        pattern: Pattern::OutsideBar
+                    },
+                    Match {
+                        index: 2,
+                        pattern: Pattern::Doji
+                    },
+                    Match {
+                        index: 2,
+                        pattern: Pattern::Hammer
+                    },
+                    Match {
+                        index: 2,
+                        pattern: Pattern::InsideBar
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn test_tolerance() {
+            let ohlc = bar(100.0, 105.0, 95.0, 101.5);
+            let loose = Tolerance {
+                doji_body: 0.2,
+                ..Tolerance::default()
+            };
+
+            assert!(!detect(std::slice::from_ref(&ohlc), &Tolerance::default())
+                .iter()
+                .any(|m| m.pattern == Pattern::Doji));
+            assert!(detect(&[ohlc], &loose)
+                .iter()
+                .any(|m| m.pattern == Pattern::Doji));
+        }
+    }
+}
@@ -0,0 +1,321 @@
+pub mod pnf {
+    use crate::charts::charts::Source;
+    use crate::ohlc::ohlc::OHLC;
+
+    /// Allows for floating point error when mapping a price to a box.
+    const EPSILON: f64 = 1e-9;
+
+    /// The mark used for the boxes of a column.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum Mark {
+        /// A rising column.
+        X,
+        /// A falling column.
+        O,
+    }
+
+    /// A column of boxes in a Point & Figure chart.
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct Column {
+        pub mark: Mark,
+        /// The timestamp of the OHLC that started the column.
+        pub start_ts: u64,
+        /// The timestamp of the OHLC that last added a box.
+        pub ts: u64,
+        box_size: f64,
+        bottom: i64,
+        top: i64,
+    }
+
+    impl Column {
+        /// The price of the lowest box.
+        pub fn low(&self) -> f64 {
+            self.bottom as f64 * self.box_size
+        }
+
+        /// The price of the highest box.
+        pub fn high(&self) -> f64 {
+            self.top as f64 * self.box_size
+        }
+
+        /// The number of boxes in the column.
+        pub fn boxes(&self) -> usize {
+            (self.top - self.bottom + 1) as usize
+        }
+
+        /// The price of each box, from the lowest.
+        pub fn levels(&self) -> Vec<f64> {
+            (self.bottom..=self.top)
+                .map(|level| level as f64 * self.box_size)
+                .collect()
+        }
+    }
+
+    /// A breakout pattern.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum Pattern {
+        /// An X column rising above the previous X column.
+        DoubleTopBreakout,
+        /// An O column falling below the previous O column.
+        DoubleBottomBreakdown,
+        /// An X column rising above the previous two X columns, which share a high.
+        TripleTopBreakout,
+        /// An O column falling below the previous two O columns, which share a low.
+        TripleBottomBreakdown,
+    }
+
+    /// A breakout found while building a chart.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct Signal {
+        /// The index of the column that broke out.
+        pub column: usize,
+        pub pattern: Pattern,
+        /// The price of the first box beyond the previous column.
+        pub price: f64,
+        pub ts: u64,
+    }
+
+    /// A Point & Figure chart, built one OHLC at a time.
+    ///
+    /// Prices are mapped to boxes of a fixed size, with X columns using the highest box reached
+    /// and O columns the lowest. A column reverses once price moves `reversal` boxes beyond its
+    /// extreme, and the new column starts one box away from it.
+    #[derive(Debug, Clone)]
+    pub struct PointAndFigure {
+        box_size: f64,
+        reversal: i64,
+        source: Source,
+        /// The boxes reached by the first price, before the first column.
+        start: Option<(i64, i64)>,
+        columns: Vec<Column>,
+        signals: Vec<Signal>,
+    }
+
+    impl PointAndFigure {
+        /// Return a new, empty chart. A reversal of zero boxes is treated as one.
+        pub fn new(box_size: f64, reversal: usize, source: Source) -> Self {
+            PointAndFigure {
+                box_size,
+                reversal: reversal.max(1) as i64,
+                source,
+                start: None,
+                columns: Vec::new(),
+                signals: Vec::new(),
+            }
+        }
+
+        /// Builds a chart from a slice of OHLC.
+        pub fn from_ohlcs(ohlcs: &[OHLC], box_size: f64, reversal: usize, source: Source) -> Self {
+            let mut chart = Self::new(box_size, reversal, source);
+            for ohlc in ohlcs {
+                chart.push(ohlc);
+            }
+            chart
+        }
+
+        /// Add an OHLC, returning any signals it triggered.
+        ///
+        /// A box size that is not positive never adds a column.
+        pub fn push(&mut self, ohlc: &OHLC) -> Vec<Signal> {
+            let before = self.signals.len();
+            if self.box_size.is_finite() && self.box_size > 0.0 {
+                for price in self.source.prices(ohlc) {
+                    self.step(price, ohlc.ts);
+                }
+            }
+            self.signals[before..].to_vec()
+        }
+
+        /// The columns built so far.
+        pub fn columns(&self) -> &[Column] {
+            &self.columns
+        }
+
+        /// The signals produced so far.
+        pub fn signals(&self) -> &[Signal] {
+            &self.signals
+        }
+
+        fn step(&mut self, price: f64, ts: u64) {
+            let up = (price / self.box_size + EPSILON).floor() as i64;
+            let down = (price / self.box_size - EPSILON).ceil() as i64;
+
+            let Some(column) = self.columns.last_mut() else {
+                let (start_up, start_down) = *self.start.get_or_insert((up, down));
+                if up > start_up {
+                    self.add(Mark::X, start_up + 1, up, ts);
+                } else if down < start_down {
+                    self.add(Mark::O, down, start_down - 1, ts);
+                }
+                return;
+            };
+
+            match column.mark {
+                Mark::X if up > column.top => {
+                    let from = column.top;
+                    column.top = up;
+                    column.ts = ts;
+                    self.check(from);
+                }
+                Mark::X if down <= column.top - self.reversal => {
+                    let top = column.top - 1;
+                    self.add(Mark::O, down, top, ts);
+                }
+                Mark::O if down < column.bottom => {
+                    let from = column.bottom;
+                    column.bottom = down;
+                    column.ts = ts;
+                    self.check(from);
+                }
+                Mark::O if up >= column.bottom + self.reversal => {
+                    let bottom = column.bottom + 1;
+                    self.add(Mark::X, bottom, up, ts);
+                }
+                _ => {}
+            }
+        }
+
+        fn add(&mut self, mark: Mark, bottom: i64, top: i64, ts: u64) {
+            self.columns.push(Column {
+                mark,
+                start_ts: ts,
+                ts,
+                box_size: self.box_size,
+                bottom,
+                top,
+            });
+
+            match mark {
+                Mark::X => self.check(bottom - 1),
+                Mark::O => self.check(top + 1),
+            }
+        }
+
+        /// Records a signal if the last column has just passed the previous column of its mark,
+        /// having been at `from` before.
+        fn check(&mut self, from: i64) {
+            let n = self.columns.len();
+            let Some(prev) = n.checked_sub(3).map(|i| &self.columns[i]) else {
+                return;
+            };
+            let column = &self.columns[n - 1];
+            let earlier = n.checked_sub(5).map(|i| &self.columns[i]);
+
+            let (pattern, level) = match column.mark {
+                Mark::X if from <= prev.top && column.top > prev.top => {
+                    let triple = earlier.is_some_and(|e| e.top == prev.top);
+                    let pattern = if triple {
+                        Pattern::TripleTopBreakout
+                    } else {
+                        Pattern::DoubleTopBreakout
+                    };
+                    (pattern, prev.top + 1)
+                }
+                Mark::O if from >= prev.bottom && column.bottom < prev.bottom => {
+                    let triple = earlier.is_some_and(|e| e.bottom == prev.bottom);
+                    let pattern = if triple {
+                        Pattern::TripleBottomBreakdown
+                    } else {
+                        Pattern::DoubleBottomBreakdown
+                    };
+                    (pattern, prev.bottom - 1)
+                }
+                _ => return,
+            };
+
+            self.signals.push(Signal {
+                column: n - 1,
+                pattern,
+                price: level as f64 * self.box_size,
+                ts: column.ts,
+            });
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn closes(closes: &[f64]) -> Vec<OHLC> {
+            closes
+                .iter()
+                .enumerate()
+                .map(|(i, &c)| OHLC::new(c, c, c, c, i as u64 + 1))
+                .collect()
+        }
+
+        fn summary(chart: &PointAndFigure) -> Vec<(Mark, f64, f64, usize)> {
+            chart
+                .columns()
+                .iter()
+                .map(|c| (c.mark, c.low(), c.high(), c.boxes()))
+                .collect()
+        }
+
+        #[test]
+        fn test_columns() {
+            let ohlcs = closes(&[100.0, 103.0, 102.0, 100.0, 99.0, 104.0, 101.0, 98.0]);
+
+            let chart = PointAndFigure::from_ohlcs(&ohlcs, 1.0, 3, Source::Close);
+
+            assert_eq!(
+                summary(&chart),
+                vec![
+                    (Mark::X, 101.0, 103.0, 3),
+                    (Mark::O, 99.0, 102.0, 4),
+                    (Mark::X, 100.0, 104.0, 5),
+                    (Mark::O, 98.0, 103.0, 6),
+                ]
+            );
+            assert_eq!(chart.columns()[1].levels(), vec![99.0, 100.0, 101.0, 102.0]);
+            assert_eq!(chart.columns()[1].start_ts, 4);
+            assert_eq!(chart.columns()[1].ts, 5);
+            assert_eq!(
+                chart.signals(),
+                &[
+                    Signal {
+                        column: 2,
+                        pattern: Pattern::DoubleTopBreakout,
+                        price: 104.0,
+                        ts: 6
+                    },
+                    Signal {
+                        column: 3,
+                        pattern: Pattern::DoubleBottomBreakdown,
+                        price: 98.0,
+                        ts: 8
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn test_triple_top() {
+            let ohlcs = closes(&[100.0, 103.0, 100.0, 103.0, 100.0, 104.0, 105.0]);
+            let mut chart = PointAndFigure::new(1.0, 3, Source::Close);
+
+            let signals: Vec<Vec<Signal>> = ohlcs.iter().map(|ohlc| chart.push(ohlc)).collect();
+
+            assert_eq!(signals[5].len(), 1);
+            assert_eq!(signals[5][0].pattern, Pattern::TripleTopBreakout);
+            assert_eq!(signals[5][0].price, 104.0);
+            assert!(signals[6].is_empty(), "Breakout should only signal once");
+        }
+
+        #[test]
+        fn test_high_low() {
+            let ohlcs = vec![
+                OHLC::new(10.0, 10.0, 10.0, 10.0, 1),
+                OHLC::new(10.0, 10.9, 9.9, 10.8, 2),
+                OHLC::new(10.8, 10.8, 10.0, 10.1, 3),
+            ];
+
+            let chart = PointAndFigure::from_ohlcs(&ohlcs, 0.25, 2, Source::HighLow);
+
+            assert_eq!(
+                summary(&chart),
+                vec![(Mark::X, 10.25, 10.75, 3), (Mark::O, 10.0, 10.5, 3)]
+            );
+        }
+    }
+}
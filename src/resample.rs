@@ -0,0 +1,422 @@
+pub mod resample {
+    use crate::ohlc::ohlc::OHLC;
+    use crate::timeframe::timeframe::{
+        civil_from_days, days_from_civil, month_number, month_start, week_start, SECONDS_PER_DAY,
+    };
+    pub use crate::timestamp::timestamp::Unit;
+    use std::fmt;
+
+    /// Calendar periods, aligned to UTC. Weeks start on Monday.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum Calendar {
+        Day,
+        Week,
+        Month,
+        Year,
+    }
+
+    /// The bucket OHLC values are aggregated into.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub enum Bucket {
+        /// A fixed width, in the same units as the OHLC timestamps.
+        Fixed(u64),
+        /// A calendar period for timestamps in the given unit.
+        Calendar(Calendar, Unit),
+    }
+
+    /// What to do with a bucket that is only partially covered by the input.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum Partial {
+        Keep,
+        Drop,
+    }
+
+    /// Options for resampling OHLC slices.
+    pub struct Opts {
+        pub bucket: Bucket,
+        /// Shifts bucket boundaries forward, in the same units as the OHLC timestamps.
+        pub offset: u64,
+        pub leading: Partial,
+        pub trailing: Partial,
+    }
+
+    /// Errors returned by `resample`.
+    #[derive(Debug, PartialEq, Clone)]
+    pub enum ResampleError {
+        /// A fixed bucket must have a non-zero width.
+        ZeroWidth,
+        /// The input is not sorted by timestamp. Holds the index of the first offending OHLC.
+        Unsorted { index: usize },
+    }
+
+    impl fmt::Display for ResampleError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                ResampleError::ZeroWidth => write!(f, "Bucket width must be non-zero"),
+                ResampleError::Unsorted { index } => {
+                    write!(f, "OHLC at index {} is out of timestamp order", index)
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for ResampleError {}
+
+    /// Aggregates a slice of OHLC, sorted by timestamp, into coarser buckets.
+    ///
+    /// Each output OHLC is stamped with the start of its bucket, or with the timestamp of its
+    /// first input if an offset starts the bucket before the epoch. Buckets without any input are
+    /// skipped, so gaps in the input remain gaps in the output. Volume is summed, and is `None`
+    /// only when no OHLC in the bucket had a volume.
+    ///
+    /// The leading bucket is partial when the first OHLC does not start on a bucket boundary. The
+    /// trailing bucket is partial when the last OHLC does not reach the end of its bucket, using
+    /// the smallest spacing between input timestamps as the OHLC width.
+    pub fn resample(ohlcs: &[OHLC], opts: Opts) -> Result<Vec<OHLC>, ResampleError> {
+        if opts.bucket == Bucket::Fixed(0) {
+            return Err(ResampleError::ZeroWidth);
+        }
+
+        if let Some(index) = (1..ohlcs.len()).find(|&i| ohlcs[i].ts < ohlcs[i - 1].ts) {
+            return Err(ResampleError::Unsorted { index });
+        }
+
+        // Buckets are keyed by their unclamped start, which is before zero when an offset shifts
+        // the first bucket back past the epoch.
+        let mut buckets: Vec<(i128, OHLC)> = Vec::new();
+
+        for ohlc in ohlcs {
+            let (start, _) = bounds(ohlc.ts, &opts);
+
+            match buckets.last_mut() {
+                Some((last_start, agg)) if *last_start == start => {
+                    agg.high = agg.high.max(ohlc.high);
+                    agg.low = agg.low.min(ohlc.low);
+                    agg.close = ohlc.close;
+                    agg.vol = match (agg.vol, ohlc.vol) {
+                        (Some(a), Some(b)) => Some(a + b),
+                        (a, b) => a.or(b),
+                    };
+                }
+                _ => {
+                    let mut agg = ohlc.clone();
+                    agg.ts = u64::try_from(start).unwrap_or(ohlc.ts);
+                    buckets.push((start, agg));
+                }
+            }
+        }
+
+        if opts.leading == Partial::Drop {
+            if let (Some(first), Some((start, _))) = (ohlcs.first(), buckets.first()) {
+                if first.ts as i128 != *start {
+                    buckets.remove(0);
+                }
+            }
+        }
+
+        if opts.trailing == Partial::Drop {
+            if let (Some(last), Some(width)) = (ohlcs.last(), min_spacing(ohlcs)) {
+                if let Some((last_start, _)) = buckets.last() {
+                    let (start, end) = bounds(last.ts, &opts);
+                    if *last_start == start && (last.ts as i128) + (width as i128) < end {
+                        buckets.pop();
+                    }
+                }
+            }
+        }
+
+        Ok(buckets.into_iter().map(|(_, ohlc)| ohlc).collect())
+    }
+
+    /// Smallest positive spacing between consecutive timestamps.
+    fn min_spacing(ohlcs: &[OHLC]) -> Option<u64> {
+        ohlcs
+            .windows(2)
+            .map(|pair| pair[1].ts - pair[0].ts)
+            .filter(|&d| d > 0)
+            .min()
+    }
+
+    /// The start and end of the bucket containing a timestamp, before clamping to the range of
+    /// a timestamp.
+    fn bounds(ts: u64, opts: &Opts) -> (i128, i128) {
+        let offset = opts.offset as i128;
+        let shifted = ts as i128 - offset;
+
+        let (start, end) = match opts.bucket {
+            Bucket::Fixed(width) => {
+                let width = width as i128;
+                let start = shifted.div_euclid(width) * width;
+                (start, start + width)
+            }
+            Bucket::Calendar(calendar, unit) => {
+                let per_second = unit.per_second() as i128;
+                let per_day = SECONDS_PER_DAY as i128 * per_second;
+                let days = calendar_start(shifted.div_euclid(per_day) as i64, calendar);
+                let end_days = calendar_next(days, calendar);
+                (days as i128 * per_day, end_days as i128 * per_day)
+            }
+        };
+
+        (start + offset, end + offset)
+    }
+
+    /// Day number of the start of the calendar period containing `days`.
+    pub(crate) fn calendar_start(days: i64, calendar: Calendar) -> i64 {
+        match calendar {
+            Calendar::Day => days,
+            Calendar::Week => week_start(days, 1),
+            Calendar::Month => month_start(month_number(days)),
+            Calendar::Year => {
+                let (y, _, _) = civil_from_days(days);
+                days_from_civil(y, 1, 1)
+            }
+        }
+    }
+
+    /// Day number of the start of the calendar period following the one starting at `days`.
+    pub(crate) fn calendar_next(days: i64, calendar: Calendar) -> i64 {
+        match calendar {
+            Calendar::Day => days + 1,
+            Calendar::Week => days + 7,
+            Calendar::Month => month_start(month_number(days) + 1),
+            Calendar::Year => {
+                let (y, _, _) = civil_from_days(days);
+                days_from_civil(y + 1, 1, 1)
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn bar(open: f64, high: f64, low: f64, close: f64, ts: u64) -> OHLC {
+            OHLC::new(open, high, low, close, ts)
+        }
+
+        fn keep(bucket: Bucket) -> Opts {
+            Opts {
+                bucket,
+                offset: 0,
+                leading: Partial::Keep,
+                trailing: Partial::Keep,
+            }
+        }
+
+        #[test]
+        fn test_resample_fixed() {
+            let ohlcs = vec![
+                bar(10.0, 12.0, 9.0, 11.0, 0).with_volume(1.0),
+                bar(11.0, 15.0, 10.0, 14.0, 60).with_volume(2.0),
+                bar(14.0, 14.5, 8.0, 9.0, 120).with_volume(3.0),
+                bar(9.0, 10.0, 7.0, 8.0, 180),
+                bar(8.0, 9.0, 6.0, 7.0, 240).with_volume(4.0),
+                bar(7.0, 8.0, 5.0, 6.0, 300).with_volume(5.0),
+            ];
+
+            let result = resample(&ohlcs, keep(Bucket::Fixed(300))).unwrap();
+            let expected = vec![
+                bar(10.0, 15.0, 6.0, 7.0, 0).with_volume(10.0),
+                bar(7.0, 8.0, 5.0, 6.0, 300).with_volume(5.0),
+            ];
+
+            assert_eq!(result, expected, "Buckets should aggregate OHLC");
+        }
+
+        #[test]
+        fn test_resample_volume_none() {
+            let ohlcs = vec![bar(1.0, 2.0, 0.5, 1.5, 0), bar(1.5, 3.0, 1.0, 2.5, 60)];
+
+            let result = resample(&ohlcs, keep(Bucket::Fixed(120))).unwrap();
+
+            assert_eq!(result, vec![bar(1.0, 3.0, 0.5, 2.5, 0)]);
+        }
+
+        #[test]
+        fn test_resample_gap() {
+            let ohlcs = vec![
+                bar(1.0, 2.0, 0.5, 1.5, 0),
+                bar(1.5, 3.0, 1.0, 2.5, 60),
+                bar(2.5, 4.0, 2.0, 3.5, 600),
+            ];
+
+            let result = resample(&ohlcs, keep(Bucket::Fixed(120))).unwrap();
+            let expected = vec![bar(1.0, 3.0, 0.5, 2.5, 0), bar(2.5, 4.0, 2.0, 3.5, 600)];
+
+            assert_eq!(result, expected, "Empty buckets should be skipped");
+        }
+
+        #[test]
+        fn test_resample_partial() {
+            struct TestCase {
+                name: &'static str,
+                leading: Partial,
+                trailing: Partial,
+                expected: Vec<u64>,
+            }
+
+            // Buckets of 180 covering [60, 180), [180, 360) and [360, 540)
+            let ohlcs: Vec<OHLC> = [60, 120, 180, 240, 300, 360]
+                .iter()
+                .map(|&ts| bar(1.0, 2.0, 0.5, 1.5, ts))
+                .collect();
+
+            let test_cases = vec![
+                TestCase {
+                    name: "keep both",
+                    leading: Partial::Keep,
+                    trailing: Partial::Keep,
+                    expected: vec![0, 180, 360],
+                },
+                TestCase {
+                    name: "drop leading",
+                    leading: Partial::Drop,
+                    trailing: Partial::Keep,
+                    expected: vec![180, 360],
+                },
+                TestCase {
+                    name: "drop trailing",
+                    leading: Partial::Keep,
+                    trailing: Partial::Drop,
+                    expected: vec![0, 180],
+                },
+                TestCase {
+                    name: "drop both",
+                    leading: Partial::Drop,
+                    trailing: Partial::Drop,
+                    expected: vec![180],
+                },
+            ];
+
+            for case in test_cases {
+                let opts = Opts {
+                    bucket: Bucket::Fixed(180),
+                    offset: 0,
+                    leading: case.leading,
+                    trailing: case.trailing,
+                };
+                let result: Vec<u64> = resample(&ohlcs, opts)
+                    .unwrap()
+                    .iter()
+                    .map(|ohlc| ohlc.ts)
+                    .collect();
+
+                assert_eq!(result, case.expected, "Test case '{}' failed", case.name);
+            }
+        }
+
+        #[test]
+        fn test_resample_complete_trailing_kept() {
+            let ohlcs: Vec<OHLC> = [0, 60, 120, 180]
+                .iter()
+                .map(|&ts| bar(1.0, 2.0, 0.5, 1.5, ts))
+                .collect();
+            let opts = Opts {
+                bucket: Bucket::Fixed(120),
+                offset: 0,
+                leading: Partial::Drop,
+                trailing: Partial::Drop,
+            };
+
+            let result = resample(&ohlcs, opts).unwrap();
+
+            assert_eq!(result.len(), 2, "Complete buckets should not be dropped");
+        }
+
+        #[test]
+        fn test_resample_offset() {
+            let ohlcs: Vec<OHLC> = [30, 60, 120, 180]
+                .iter()
+                .map(|&ts| bar(1.0, 2.0, 0.5, 1.5, ts))
+                .collect();
+
+            // The first bucket runs from -60 to 60, so starts before the first OHLC. Kept, it is
+            // stamped with that OHLC's timestamp, as it can't be stamped before the epoch.
+            let test_cases = vec![
+                ("keep", Partial::Keep, vec![30, 60, 180]),
+                ("drop", Partial::Drop, vec![60, 180]),
+            ];
+
+            for (name, leading, expected) in test_cases {
+                let opts = Opts {
+                    bucket: Bucket::Fixed(120),
+                    offset: 60,
+                    leading,
+                    trailing: Partial::Keep,
+                };
+
+                let resampled = resample(&ohlcs, opts).unwrap();
+                let result: Vec<u64> = resampled.iter().map(|ohlc| ohlc.ts).collect();
+
+                assert_eq!(result, expected, "Test case '{}' failed", name);
+                assert!(
+                    resampled.iter().all(|ohlc| ohlc.validate().is_ok()),
+                    "Test case '{}' produced invalid OHLC",
+                    name
+                );
+            }
+        }
+
+        #[test]
+        fn test_resample_calendar() {
+            // 2021-06-30 23:00, 2021-07-01 00:00, 2021-07-31 12:00, 2021-08-01 00:00 UTC
+            let ohlcs = vec![
+                bar(1.0, 2.0, 0.5, 1.5, 1625094000),
+                bar(1.5, 3.0, 1.0, 2.5, 1625097600),
+                bar(2.5, 4.0, 2.0, 3.5, 1627732800),
+                bar(3.5, 5.0, 3.0, 4.5, 1627776000),
+            ];
+
+            let millis: Vec<OHLC> = ohlcs
+                .iter()
+                .map(|ohlc| bar(ohlc.open, ohlc.high, ohlc.low, ohlc.close, ohlc.ts * 1000))
+                .collect();
+
+            let test_cases = vec![
+                (
+                    "month",
+                    &ohlcs,
+                    Bucket::Calendar(Calendar::Month, Unit::Seconds),
+                    vec![1622505600, 1625097600, 1627776000],
+                ),
+                (
+                    "week",
+                    &ohlcs,
+                    Bucket::Calendar(Calendar::Week, Unit::Seconds),
+                    vec![1624838400, 1627257600],
+                ),
+                (
+                    "year in millis",
+                    &millis,
+                    Bucket::Calendar(Calendar::Year, Unit::Millis),
+                    vec![1609459200000],
+                ),
+            ];
+
+            for (name, input, bucket, expected) in test_cases {
+                let result: Vec<u64> = resample(input, keep(bucket))
+                    .unwrap()
+                    .iter()
+                    .map(|ohlc| ohlc.ts)
+                    .collect();
+
+                assert_eq!(result, expected, "Test case '{}' failed", name);
+            }
+        }
+
+        #[test]
+        fn test_resample_errors() {
+            let ohlcs = vec![bar(1.0, 2.0, 0.5, 1.5, 120), bar(1.5, 3.0, 1.0, 2.5, 60)];
+
+            assert_eq!(
+                resample(&ohlcs, keep(Bucket::Fixed(0))),
+                Err(ResampleError::ZeroWidth)
+            );
+            assert_eq!(
+                resample(&ohlcs, keep(Bucket::Fixed(60))),
+                Err(ResampleError::Unsorted { index: 1 })
+            );
+        }
+    }
+}
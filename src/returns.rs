@@ -0,0 +1,252 @@
+pub mod returns {
+    use crate::ohlc::ohlc::OHLC;
+    use std::cmp::Ordering;
+
+    /// A return for the period ending at `ts`.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct Return {
+        pub ts: u64,
+        pub value: f64,
+    }
+
+    impl Return {
+        /// Return a new Return.
+        pub fn new(ts: u64, value: f64) -> Self {
+            Return { ts, value }
+        }
+    }
+
+    /// How a return is calculated from two prices.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum Method {
+        /// `to / from - 1`.
+        Simple,
+        /// `ln(to / from)`.
+        Log,
+    }
+
+    impl Method {
+        /// The return from one price to another.
+        pub fn between(&self, from: f64, to: f64) -> f64 {
+            match self {
+                Method::Simple => to / from - 1.0,
+                Method::Log => (to / from).ln(),
+            }
+        }
+    }
+
+    /// All values from a slice of returns.
+    pub fn values(returns: &[Return]) -> Vec<f64> {
+        returns.iter().map(|r| r.value).collect()
+    }
+
+    /// Close to close returns, one fewer than the OHLC, stamped with the later OHLC.
+    pub fn returns(ohlcs: &[OHLC], method: Method) -> Vec<Return> {
+        returns_over(ohlcs, 1, method)
+    }
+
+    /// Close to close returns over `n` OHLC, stamped with the later OHLC.
+    ///
+    /// Returns nothing if `n` is zero.
+    pub fn returns_over(ohlcs: &[OHLC], n: usize, method: Method) -> Vec<Return> {
+        if n == 0 {
+            return Vec::new();
+        }
+
+        ohlcs
+            .iter()
+            .zip(ohlcs.iter().skip(n))
+            .map(|(from, to)| Return::new(to.ts, method.between(from.close, to.close)))
+            .collect()
+    }
+
+    /// The return from the open to the close of each OHLC.
+    pub fn open_to_close(ohlcs: &[OHLC], method: Method) -> Vec<Return> {
+        ohlcs
+            .iter()
+            .map(|ohlc| Return::new(ohlc.ts, method.between(ohlc.open, ohlc.close)))
+            .collect()
+    }
+
+    /// The overnight return from each close to the next open, stamped with the later OHLC.
+    pub fn close_to_open(ohlcs: &[OHLC], method: Method) -> Vec<Return> {
+        ohlcs
+            .windows(2)
+            .map(|w| Return::new(w[1].ts, method.between(w[0].close, w[1].open)))
+            .collect()
+    }
+
+    /// The cumulative return to each period, compounding simple returns and summing log returns.
+    pub fn cumulative(returns: &[Return], method: Method) -> Vec<Return> {
+        let mut total = 0.0;
+
+        returns
+            .iter()
+            .map(|r| {
+                total = match method {
+                    Method::Simple => (1.0 + total) * (1.0 + r.value) - 1.0,
+                    Method::Log => total + r.value,
+                };
+                Return::new(r.ts, total)
+            })
+            .collect()
+    }
+
+    /// Returns in excess of a fixed risk free rate per period.
+    pub fn excess(returns: &[Return], risk_free: f64) -> Vec<Return> {
+        returns
+            .iter()
+            .map(|r| Return::new(r.ts, r.value - risk_free))
+            .collect()
+    }
+
+    /// Returns in excess of a benchmark, for the timestamps found in both.
+    ///
+    /// Both slices must be in timestamp order.
+    pub fn excess_over(returns: &[Return], benchmark: &[Return]) -> Vec<Return> {
+        let mut result = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        while let (Some(r), Some(b)) = (returns.get(i), benchmark.get(j)) {
+            match r.ts.cmp(&b.ts) {
+                Ordering::Less => i += 1,
+                Ordering::Greater => j += 1,
+                Ordering::Equal => {
+                    result.push(Return::new(r.ts, r.value - b.value));
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+
+        result
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const EPSILON: f64 = 1e-12;
+
+        fn fixture() -> Vec<OHLC> {
+            vec![
+                OHLC::new(99.0, 101.0, 98.0, 100.0, 1000),
+                OHLC::new(101.0, 111.0, 100.0, 110.0, 2000),
+                OHLC::new(108.0, 110.0, 98.0, 99.0, 3000),
+                OHLC::new(100.0, 122.0, 99.0, 121.0, 4000),
+            ]
+        }
+
+        fn assert_returns(result: &[Return], expected: &[(u64, f64)], name: &str) {
+            assert_eq!(result.len(), expected.len(), "Test case '{}' length", name);
+            for (r, (ts, value)) in result.iter().zip(expected) {
+                assert_eq!(r.ts, *ts, "Test case '{}' ts", name);
+                assert!(
+                    (r.value - value).abs() < EPSILON,
+                    "Test case '{}' at {}: got {}, expected {}",
+                    name,
+                    ts,
+                    r.value,
+                    value
+                );
+            }
+        }
+
+        #[test]
+        fn test_returns() {
+            let ohlcs = fixture();
+
+            let test_cases = vec![
+                (
+                    "simple",
+                    returns(&ohlcs, Method::Simple),
+                    vec![(2000, 0.1), (3000, -0.1), (4000, 121.0 / 99.0 - 1.0)],
+                ),
+                (
+                    "log",
+                    returns(&ohlcs, Method::Log),
+                    vec![
+                        (2000, 1.1f64.ln()),
+                        (3000, 0.9f64.ln()),
+                        (4000, (121.0f64 / 99.0).ln()),
+                    ],
+                ),
+                (
+                    "over two",
+                    returns_over(&ohlcs, 2, Method::Simple),
+                    vec![(3000, -0.01), (4000, 0.1)],
+                ),
+                ("over zero", returns_over(&ohlcs, 0, Method::Simple), vec![]),
+                (
+                    "open to close",
+                    open_to_close(&ohlcs[..2], Method::Simple),
+                    vec![(1000, 100.0 / 99.0 - 1.0), (2000, 110.0 / 101.0 - 1.0)],
+                ),
+                (
+                    "close to open",
+                    close_to_open(&ohlcs, Method::Simple),
+                    vec![
+                        (2000, 0.01),
+                        (3000, 108.0 / 110.0 - 1.0),
+                        (4000, 100.0 / 99.0 - 1.0),
+                    ],
+                ),
+                ("empty", returns(&[], Method::Simple), vec![]),
+            ];
+
+            for (name, result, expected) in test_cases {
+                assert_returns(&result, &expected, name);
+            }
+        }
+
+        #[test]
+        fn test_cumulative() {
+            let ohlcs = fixture();
+
+            let simple = cumulative(&returns(&ohlcs, Method::Simple), Method::Simple);
+            let log = cumulative(&returns(&ohlcs, Method::Log), Method::Log);
+
+            assert_returns(
+                &simple,
+                &[(2000, 0.1), (3000, -0.01), (4000, 0.21)],
+                "simple",
+            );
+            assert_returns(
+                &log,
+                &[
+                    (2000, 1.1f64.ln()),
+                    (3000, 0.99f64.ln()),
+                    (4000, 1.21f64.ln()),
+                ],
+                "log",
+            );
+        }
+
+        #[test]
+        fn test_excess() {
+            let strategy = vec![
+                Return::new(1000, 0.02),
+                Return::new(2000, -0.01),
+                Return::new(3000, 0.03),
+            ];
+            let benchmark = vec![
+                Return::new(500, 0.5),
+                Return::new(2000, 0.01),
+                Return::new(3000, 0.01),
+            ];
+
+            assert_returns(
+                &excess(&strategy, 0.01),
+                &[(1000, 0.01), (2000, -0.02), (3000, 0.02)],
+                "fixed",
+            );
+            assert_returns(
+                &excess_over(&strategy, &benchmark),
+                &[(2000, -0.02), (3000, 0.02)],
+                "benchmark",
+            );
+            assert_eq!(values(&strategy), vec![0.02, -0.01, 0.03]);
+        }
+    }
+}
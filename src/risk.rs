@@ -0,0 +1,477 @@
+pub mod risk {
+    use crate::indicators::indicators::mean;
+    use crate::metrics::metrics::{kurtosis, skew, std_dev};
+    use crate::ohlc::ohlc::OHLC;
+    use crate::returns::returns::{returns, values, Method, Return};
+
+    /// The number of tail quantiles averaged for the Cornish-Fisher expected shortfall.
+    const TAIL_STEPS: usize = 1000;
+
+    /// How the distribution of returns is modelled.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum Model {
+        /// The empirical distribution, using overlapping compounded returns over the horizon.
+        Historical,
+        /// A normal distribution with the sample mean and standard deviation, scaled by the
+        /// horizon.
+        Gaussian,
+        /// A normal distribution adjusted for the sample skew and excess kurtosis.
+        CornishFisher,
+        /// Simulated paths of normally distributed returns compounded over the horizon, from a
+        /// seeded generator so results are repeatable.
+        MonteCarlo { paths: usize, seed: u64 },
+    }
+
+    /// Value-at-Risk and Expected Shortfall, as positive fractions of value lost.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct Estimate {
+        /// The loss that is not exceeded at the confidence level.
+        pub var: f64,
+        /// The mean loss beyond the VaR, also known as CVaR.
+        pub es: f64,
+    }
+
+    /// Estimates VaR and ES from simple returns per period, at a confidence level such as 0.99
+    /// and a horizon in periods.
+    ///
+    /// Returns None if the confidence is not between zero and one, the horizon is zero, or there
+    /// are too few returns for the model.
+    pub fn estimate(
+        returns: &[f64],
+        model: Model,
+        confidence: f64,
+        horizon: usize,
+    ) -> Option<Estimate> {
+        if !(confidence > 0.0 && confidence < 1.0) || horizon == 0 {
+            return None;
+        }
+        let tail = 1.0 - confidence;
+        let h = horizon as f64;
+
+        match model {
+            Model::Historical => {
+                let horizon_returns: Vec<f64> = returns
+                    .windows(horizon)
+                    .map(|w| w.iter().fold(1.0, |total, r| total * (1.0 + r)) - 1.0)
+                    .collect();
+                empirical(horizon_returns, tail)
+            }
+            Model::Gaussian => {
+                let mu = mean(returns) * h;
+                let sd = std_dev(returns)? * h.sqrt();
+                let z = inverse_normal(confidence);
+                Some(Estimate {
+                    var: z * sd - mu,
+                    es: sd * normal_density(z) / tail - mu,
+                })
+            }
+            Model::CornishFisher => {
+                let mu = mean(returns) * h;
+                let sd = std_dev(returns)? * h.sqrt();
+                let s = skew(returns)? / h.sqrt();
+                let k = kurtosis(returns)? / h;
+                let quantile = |p: f64| {
+                    let z = inverse_normal(p);
+                    z + (z * z - 1.0) * s / 6.0 + (z.powi(3) - 3.0 * z) * k / 24.0
+                        - (2.0 * z.powi(3) - 5.0 * z) * s * s / 36.0
+                };
+                let tail_mean = (0..TAIL_STEPS)
+                    .map(|i| quantile(tail * (i as f64 + 0.5) / TAIL_STEPS as f64))
+                    .sum::<f64>()
+                    / TAIL_STEPS as f64;
+                Some(Estimate {
+                    var: -(mu + quantile(tail) * sd),
+                    es: -(mu + tail_mean * sd),
+                })
+            }
+            Model::MonteCarlo { paths, seed } => {
+                let mu = mean(returns);
+                let sd = std_dev(returns)?;
+                let mut rng = SplitMix64(seed);
+                let simulated: Vec<f64> = (0..paths)
+                    .map(|_| {
+                        (0..horizon).fold(1.0, |total, _| {
+                            total * (1.0 + mu + sd * inverse_normal(rng.uniform()))
+                        }) - 1.0
+                    })
+                    .collect();
+                empirical(simulated, tail)
+            }
+        }
+    }
+
+    /// Estimates VaR and ES from the close to close returns of a slice of OHLC.
+    pub fn ohlc_estimate(
+        ohlcs: &[OHLC],
+        model: Model,
+        confidence: f64,
+        horizon: usize,
+    ) -> Option<Estimate> {
+        estimate(
+            &values(&returns(ohlcs, Method::Simple)),
+            model,
+            confidence,
+            horizon,
+        )
+    }
+
+    /// The returns of a portfolio holding fixed weights of each instrument, rebalanced every
+    /// period.
+    ///
+    /// Weights are fractions of the portfolio's value and may be negative for short holdings.
+    /// Returns are measured between consecutive timestamps found in every instrument, so each
+    /// instrument's return covers the same period. Each slice must be in timestamp order.
+    pub fn portfolio_returns(holdings: &[(&[OHLC], f64)]) -> Vec<Return> {
+        let Some((first, _)) = holdings.first() else {
+            return Vec::new();
+        };
+        let shared: Vec<u64> = first
+            .iter()
+            .map(|ohlc| ohlc.ts)
+            .filter(|ts| {
+                holdings
+                    .iter()
+                    .all(|(ohlcs, _)| ohlcs.binary_search_by_key(ts, |ohlc| ohlc.ts).is_ok())
+            })
+            .collect();
+
+        let mut totals: Vec<Return> = shared
+            .iter()
+            .skip(1)
+            .map(|&ts| Return::new(ts, 0.0))
+            .collect();
+        for (ohlcs, weight) in holdings {
+            let aligned: Vec<OHLC> = shared
+                .iter()
+                .filter_map(|ts| {
+                    let i = ohlcs.binary_search_by_key(ts, |ohlc| ohlc.ts).ok()?;
+                    Some(ohlcs[i].clone())
+                })
+                .collect();
+            for (total, r) in totals.iter_mut().zip(returns(&aligned, Method::Simple)) {
+                total.value += r.value * weight;
+            }
+        }
+        totals
+    }
+
+    /// Estimates VaR and ES for a portfolio holding fixed weights of each instrument.
+    pub fn portfolio_estimate(
+        holdings: &[(&[OHLC], f64)],
+        model: Model,
+        confidence: f64,
+        horizon: usize,
+    ) -> Option<Estimate> {
+        estimate(
+            &values(&portfolio_returns(holdings)),
+            model,
+            confidence,
+            horizon,
+        )
+    }
+
+    /// VaR from the worst `tail` fraction of returns, and ES as their mean.
+    fn empirical(mut returns: Vec<f64>, tail: f64) -> Option<Estimate> {
+        if returns.is_empty() {
+            return None;
+        }
+        returns.sort_by(f64::total_cmp);
+
+        // Guard against values like 5.0000001 returns from floating point multiplication.
+        let count = ((tail * returns.len() as f64 - 1e-9).ceil() as usize).max(1);
+        let worst = &returns[..count];
+
+        Some(Estimate {
+            var: -worst[count - 1],
+            es: -mean(worst),
+        })
+    }
+
+    fn normal_density(z: f64) -> f64 {
+        (-0.5 * z * z).exp() / (2.0 * std::f64::consts::PI).sqrt()
+    }
+
+    /// The inverse of the standard normal CDF, using Wichura's algorithm AS241.
+    fn inverse_normal(p: f64) -> f64 {
+        const A: [f64; 8] = [
+            3.387_132_872_796_366_6,
+            133.141_667_891_784_38,
+            1_971.590_950_306_551_3,
+            13_731.693_765_509_46,
+            45_921.953_931_549_87,
+            67_265.770_927_008_7,
+            33_430.575_583_588_13,
+            2_509.080_928_730_122_7,
+        ];
+        const B: [f64; 8] = [
+            1.0,
+            42.313_330_701_600_91,
+            687.187_007_492_057_9,
+            5_394.196_021_424_751,
+            21_213.794_301_586_596,
+            39_307.895_800_092_71,
+            28_729.085_735_721_943,
+            5_226.495_278_852_854,
+        ];
+        const C: [f64; 8] = [
+            1.423_437_110_749_683_6,
+            4.630_337_846_156_545,
+            5.769_497_221_460_691,
+            3.647_848_324_763_204_5,
+            1.270_458_252_452_368_4,
+            0.241_780_725_177_450_6,
+            0.022_723_844_989_269_184,
+            0.000_774_545_014_278_341_4,
+        ];
+        const D: [f64; 8] = [
+            1.0,
+            2.053_191_626_637_759,
+            1.676_384_830_183_803_8,
+            0.689_767_334_985_1,
+            0.148_103_976_427_480_07,
+            0.015_198_666_563_616_457,
+            0.000_547_593_808_499_534_5,
+            1.050_750_071_644_416_8e-9,
+        ];
+        const E: [f64; 8] = [
+            6.657_904_643_501_103,
+            5.463_784_911_164_114,
+            1.784_826_539_917_291_3,
+            0.296_560_571_828_504_9,
+            0.026_532_189_526_576_124,
+            0.001_242_660_947_388_078_4,
+            2.711_555_568_743_487_6e-5,
+            2.010_334_399_292_288_1e-7,
+        ];
+        const F: [f64; 8] = [
+            1.0,
+            0.599_832_206_555_887_9,
+            0.136_929_880_922_735_8,
+            0.014_875_361_290_850_615,
+            0.000_786_869_131_145_613_3,
+            1.846_318_317_510_054_8e-5,
+            1.421_511_758_316_446e-7,
+            2.044_263_103_389_939_8e-15,
+        ];
+
+        fn ratio(num: &[f64], den: &[f64], x: f64) -> f64 {
+            let poly = |c: &[f64]| c.iter().rev().fold(0.0, |acc, c| acc * x + c);
+            poly(num) / poly(den)
+        }
+
+        let q = p - 0.5;
+        if q.abs() <= 0.425 {
+            return q * ratio(&A, &B, 0.180625 - q * q);
+        }
+
+        let r = (-(if q < 0.0 { p } else { 1.0 - p }).ln()).sqrt();
+        let x = if r <= 5.0 {
+            ratio(&C, &D, r - 1.6)
+        } else {
+            ratio(&E, &F, r - 5.0)
+        };
+
+        if q < 0.0 {
+            -x
+        } else {
+            x
+        }
+    }
+
+    /// A small, seedable generator for repeatable simulations.
+    struct SplitMix64(u64);
+
+    impl SplitMix64 {
+        fn next(&mut self) -> u64 {
+            self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = self.0;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        }
+
+        /// A uniform value strictly between zero and one.
+        fn uniform(&mut self) -> f64 {
+            ((self.next() >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const EPSILON: f64 = 1e-12;
+
+        const RETURNS: [f64; 20] = [
+            0.012, -0.008, 0.004, -0.021, 0.015, 0.007, -0.013, 0.002, 0.019, -0.004, -0.031,
+            0.011, 0.006, -0.009, 0.003, 0.024, -0.017, 0.008, -0.002, 0.01,
+        ];
+
+        fn assert_estimate(result: Option<Estimate>, expected: (f64, f64), name: &str) {
+            let result = result.unwrap_or_else(|| panic!("{} should have an estimate", name));
+            assert!(
+                (result.var - expected.0).abs() < EPSILON
+                    && (result.es - expected.1).abs() < EPSILON,
+                "Test case '{}' failed: got {:?}, expected {:?}",
+                name,
+                result,
+                expected
+            );
+        }
+
+        fn closes(closes: &[f64], ts: &[u64]) -> Vec<OHLC> {
+            closes
+                .iter()
+                .zip(ts)
+                .map(|(&c, &ts)| OHLC::new(c, c, c, c, ts))
+                .collect()
+        }
+
+        #[test]
+        fn test_estimate() {
+            // Reference values calculated independently.
+            let test_cases = vec![
+                ("historical", Model::Historical, 0.9, 1, (0.021, 0.026)),
+                (
+                    "historical horizon",
+                    Model::Historical,
+                    0.9,
+                    5,
+                    (0.0272395470716561, 0.02731091799145602),
+                ),
+                (
+                    "gaussian",
+                    Model::Gaussian,
+                    0.99,
+                    1,
+                    (0.03173912137648205, 0.03647891687132757),
+                ),
+                (
+                    "gaussian horizon",
+                    Model::Gaussian,
+                    0.99,
+                    10,
+                    (0.09489773661035657, 0.10988628601747331),
+                ),
+                (
+                    "cornish-fisher",
+                    Model::CornishFisher,
+                    0.99,
+                    1,
+                    (0.03482270236512168, 0.03938734015031985),
+                ),
+                (
+                    "cornish-fisher horizon",
+                    Model::CornishFisher,
+                    0.95,
+                    10,
+                    (0.06688311313202679, 0.08696467013120801),
+                ),
+            ];
+
+            for (name, model, confidence, horizon, expected) in test_cases {
+                assert_estimate(
+                    estimate(&RETURNS, model, confidence, horizon),
+                    expected,
+                    name,
+                );
+            }
+        }
+
+        #[test]
+        fn test_monte_carlo() {
+            let model = Model::MonteCarlo {
+                paths: 20_000,
+                seed: 42,
+            };
+
+            let result = estimate(&RETURNS, model, 0.99, 1).unwrap();
+            let gaussian = estimate(&RETURNS, Model::Gaussian, 0.99, 1).unwrap();
+
+            assert_eq!(Some(result), estimate(&RETURNS, model, 0.99, 1));
+            assert!(
+                (result.var / gaussian.var - 1.0).abs() < 0.05,
+                "{:?}",
+                result
+            );
+            assert!((result.es / gaussian.es - 1.0).abs() < 0.05, "{:?}", result);
+            assert!(result.es > result.var);
+        }
+
+        #[test]
+        fn test_invalid() {
+            let test_cases = vec![
+                ("confidence of one", Model::Historical, 1.0, 1, &RETURNS[..]),
+                ("confidence of zero", Model::Gaussian, 0.0, 1, &RETURNS[..]),
+                ("zero horizon", Model::Historical, 0.95, 0, &RETURNS[..]),
+                (
+                    "horizon too long",
+                    Model::Historical,
+                    0.95,
+                    21,
+                    &RETURNS[..],
+                ),
+                ("single return", Model::Gaussian, 0.95, 1, &RETURNS[..1]),
+                (
+                    "no variance",
+                    Model::CornishFisher,
+                    0.95,
+                    1,
+                    &[0.01, 0.01][..],
+                ),
+            ];
+
+            for (name, model, confidence, horizon, returns) in test_cases {
+                assert_eq!(
+                    estimate(returns, model, confidence, horizon),
+                    None,
+                    "Test case '{}' failed",
+                    name
+                );
+            }
+        }
+
+        #[test]
+        fn test_inverse_normal() {
+            let test_cases = vec![
+                (0.5, 0.0),
+                (0.975, 1.9599639845400536),
+                (0.01, -2.3263478740408408),
+                (1e-10, -6.361340902404056),
+            ];
+
+            for (p, expected) in test_cases {
+                let result = inverse_normal(p);
+
+                assert!(
+                    (result - expected).abs() < EPSILON,
+                    "p {}: got {}, expected {}",
+                    p,
+                    result,
+                    expected
+                );
+            }
+        }
+
+        #[test]
+        fn test_portfolio() {
+            let a = closes(&[100.0, 110.0, 99.0, 108.9], &[1, 2, 3, 4]);
+            let b = closes(&[50.0, 51.0, 52.02], &[1, 2, 4]);
+
+            let result = portfolio_returns(&[(&a, 0.6), (&b, 0.4)]);
+
+            assert_eq!(result.len(), 2);
+            assert_eq!(result[0].ts, 2);
+            assert!((result[0].value - (0.6 * 0.1 + 0.4 * 0.02)).abs() < EPSILON);
+            // b has no close at 3, so both returns at 4 are measured from 2.
+            assert_eq!(result[1].ts, 4);
+            assert!((result[1].value - (0.6 * (108.9 / 110.0 - 1.0) + 0.4 * 0.02)).abs() < EPSILON);
+            assert!(portfolio_returns(&[]).is_empty());
+            assert!(portfolio_returns(&[(&a, 0.5), (&a[..0], 0.5)]).is_empty());
+
+            let single = portfolio_estimate(&[(&a, 1.0)], Model::Historical, 0.5, 1);
+            assert_eq!(single, ohlc_estimate(&a, Model::Historical, 0.5, 1));
+            assert_estimate(single, (-0.1, 0.0), "single");
+        }
+    }
+}
@@ -0,0 +1,410 @@
+pub mod series {
+    use crate::ohlc::ohlc::{Opts, OHLC};
+    use crate::timeframe::timeframe::Timeframe;
+    use crate::timestamp::timestamp::{Timestamp, Unit};
+    use std::fmt;
+    use std::ops::Deref;
+
+    /// Errors returned when adding OHLC to a series.
+    #[derive(Debug, PartialEq, Eq, Clone)]
+    pub enum SeriesError {
+        /// The OHLC at `index` is earlier than the one before it.
+        Unsorted { index: usize },
+        /// The OHLC at `index` has the same timestamp as the one before it.
+        DuplicateTimestamp { index: usize, ts: u64 },
+        /// The OHLC at `index` does not start on a boundary of the series timeframe.
+        Misaligned { index: usize, ts: u64 },
+    }
+
+    impl fmt::Display for SeriesError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                SeriesError::Unsorted { index } => {
+                    write!(f, "OHLC at index {} is out of timestamp order", index)
+                }
+                SeriesError::DuplicateTimestamp { index, ts } => {
+                    write!(f, "OHLC at index {} has duplicate timestamp {}", index, ts)
+                }
+                SeriesError::Misaligned { index, ts } => {
+                    write!(
+                        f,
+                        "OHLC at index {} has timestamp {} not aligned to the timeframe",
+                        index, ts
+                    )
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for SeriesError {}
+
+    /// An owned series of OHLC with strictly increasing timestamps.
+    ///
+    /// Dereferences to `[OHLC]`, so the free functions in `ohlc` accept a `&OhlcSeries` directly.
+    #[derive(Debug, PartialEq, Clone, Default)]
+    pub struct OhlcSeries {
+        ohlcs: Vec<OHLC>,
+        opens: Vec<f64>,
+        highs: Vec<f64>,
+        lows: Vec<f64>,
+        closes: Vec<f64>,
+        timestamps: Vec<u64>,
+        timeframe: Option<(Timeframe, Unit)>,
+    }
+
+    impl OhlcSeries {
+        /// Return a new, empty OhlcSeries.
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Tag the series with the timeframe of its OHLC, whose timestamps are in `unit`.
+        ///
+        /// Every OHLC already in the series, and every one pushed later, must be aligned to the
+        /// timeframe.
+        pub fn with_timeframe(
+            mut self,
+            timeframe: Timeframe,
+            unit: Unit,
+        ) -> Result<Self, SeriesError> {
+            if let Some(index) = self
+                .timestamps
+                .iter()
+                .position(|&ts| !timeframe.is_aligned(ts, unit))
+            {
+                let ts = self.timestamps[index];
+                return Err(SeriesError::Misaligned { index, ts });
+            }
+
+            self.timeframe = Some((timeframe, unit));
+            Ok(self)
+        }
+
+        /// The timeframe of the OHLC in the series, if tagged.
+        pub fn timeframe(&self) -> Option<Timeframe> {
+            self.timeframe.map(|(timeframe, _)| timeframe)
+        }
+
+        /// The unit of the timestamps in the series, if tagged with a timeframe.
+        pub fn unit(&self) -> Option<Unit> {
+            self.timeframe.map(|(_, unit)| unit)
+        }
+
+        /// Append an OHLC, which must be later than the last OHLC in the series.
+        pub fn push(&mut self, ohlc: OHLC) -> Result<(), SeriesError> {
+            let index = self.ohlcs.len();
+
+            if let Some((timeframe, unit)) = self.timeframe {
+                if !timeframe.is_aligned(ohlc.ts, unit) {
+                    return Err(SeriesError::Misaligned { index, ts: ohlc.ts });
+                }
+            }
+
+            if let Some(&last) = self.timestamps.last() {
+                if ohlc.ts == last {
+                    return Err(SeriesError::DuplicateTimestamp { index, ts: ohlc.ts });
+                }
+                if ohlc.ts < last {
+                    return Err(SeriesError::Unsorted { index });
+                }
+            }
+
+            self.opens.push(ohlc.open);
+            self.highs.push(ohlc.high);
+            self.lows.push(ohlc.low);
+            self.closes.push(ohlc.close);
+            self.timestamps.push(ohlc.ts);
+            self.ohlcs.push(ohlc);
+
+            Ok(())
+        }
+
+        /// Append several OHLC, stopping at the first one out of order.
+        pub fn extend<I: IntoIterator<Item = OHLC>>(
+            &mut self,
+            ohlcs: I,
+        ) -> Result<(), SeriesError> {
+            ohlcs.into_iter().try_for_each(|ohlc| self.push(ohlc))
+        }
+
+        /// All opens in the series.
+        pub fn opens(&self) -> &[f64] {
+            &self.opens
+        }
+
+        /// All highs in the series.
+        pub fn highs(&self) -> &[f64] {
+            &self.highs
+        }
+
+        /// All lows in the series.
+        pub fn lows(&self) -> &[f64] {
+            &self.lows
+        }
+
+        /// All closes in the series.
+        pub fn closes(&self) -> &[f64] {
+            &self.closes
+        }
+
+        /// All timestamps in the series.
+        pub fn timestamps(&self) -> &[u64] {
+            &self.timestamps
+        }
+
+        /// Binary search for a timestamp.
+        ///
+        /// Returns `Ok` with the index of a matching OHLC, or `Err` with the index where an OHLC
+        /// with that timestamp would be inserted.
+        pub fn search(&self, ts: u64) -> Result<usize, usize> {
+            self.timestamps.binary_search(&ts)
+        }
+
+        /// Returns the OHLC with the given timestamp.
+        pub fn get_at(&self, ts: u64) -> Option<&OHLC> {
+            self.search(ts).ok().map(|i| &self.ohlcs[i])
+        }
+
+        /// Returns the OHLC in effect at the given timestamp, being the last one at or before it.
+        pub fn latest_at(&self, ts: u64) -> Option<&OHLC> {
+            let end = self.timestamps.partition_point(|&t| t <= ts);
+            end.checked_sub(1).map(|i| &self.ohlcs[i])
+        }
+
+        /// Returns the OHLC with timestamps in the inclusive range `from..=to`.
+        pub fn range(&self, from: u64, to: u64) -> &[OHLC] {
+            let start = self.timestamps.partition_point(|&t| t < from);
+            let end = self.timestamps.partition_point(|&t| t <= to);
+            if start >= end {
+                &[]
+            } else {
+                &self.ohlcs[start..end]
+            }
+        }
+
+        /// Returns the OHLC matching the supplied options.
+        ///
+        /// Equivalent to `ohlc::filter` but uses a binary search and does not copy.
+        pub fn filter(&self, opts: Opts) -> &[OHLC] {
+            let ts = |t: u64| Timestamp::new(t, opts.unit);
+            let start = opts
+                .exclude_before
+                .map_or(0, |from| self.timestamps.partition_point(|&t| ts(t) < from));
+            let end = opts.exclude_after.map_or(self.len(), |to| {
+                self.timestamps.partition_point(|&t| ts(t) <= to)
+            });
+
+            if start >= end {
+                &[]
+            } else {
+                &self.ohlcs[start..end]
+            }
+        }
+
+        /// Returns the OHLC in the series as a slice.
+        pub fn as_slice(&self) -> &[OHLC] {
+            &self.ohlcs
+        }
+
+        /// Consumes the series, returning the OHLC.
+        pub fn into_vec(self) -> Vec<OHLC> {
+            self.ohlcs
+        }
+    }
+
+    impl Deref for OhlcSeries {
+        type Target = [OHLC];
+
+        fn deref(&self) -> &[OHLC] {
+            &self.ohlcs
+        }
+    }
+
+    impl AsRef<[OHLC]> for OhlcSeries {
+        fn as_ref(&self) -> &[OHLC] {
+            &self.ohlcs
+        }
+    }
+
+    impl TryFrom<Vec<OHLC>> for OhlcSeries {
+        type Error = SeriesError;
+
+        fn try_from(ohlcs: Vec<OHLC>) -> Result<Self, SeriesError> {
+            let mut series = OhlcSeries::new();
+            series.extend(ohlcs)?;
+            Ok(series)
+        }
+    }
+
+    impl<'a> IntoIterator for &'a OhlcSeries {
+        type Item = &'a OHLC;
+        type IntoIter = std::slice::Iter<'a, OHLC>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.ohlcs.iter()
+        }
+    }
+
+    impl IntoIterator for OhlcSeries {
+        type Item = OHLC;
+        type IntoIter = std::vec::IntoIter<OHLC>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.ohlcs.into_iter()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::ohlc::ohlc;
+
+        fn fake_ohlc(ts: u64) -> OHLC {
+            OHLC::new(
+                ts as f64,
+                ts as f64 + 10.0,
+                ts as f64 - 10.0,
+                ts as f64 + 5.0,
+                ts,
+            )
+        }
+
+        fn fake_series(timestamps: &[u64]) -> OhlcSeries {
+            OhlcSeries::try_from(
+                timestamps
+                    .iter()
+                    .map(|&ts| fake_ohlc(ts))
+                    .collect::<Vec<_>>(),
+            )
+            .unwrap()
+        }
+
+        #[test]
+        fn test_try_from() {
+            let test_cases = vec![
+                ("sorted", vec![1000, 2000, 3000], Ok(3)),
+                ("empty", vec![], Ok(0)),
+                (
+                    "unsorted",
+                    vec![1000, 3000, 2000],
+                    Err(SeriesError::Unsorted { index: 2 }),
+                ),
+                (
+                    "duplicate",
+                    vec![1000, 1000],
+                    Err(SeriesError::DuplicateTimestamp { index: 1, ts: 1000 }),
+                ),
+            ];
+
+            for (name, timestamps, expected) in test_cases {
+                let ohlcs: Vec<OHLC> = timestamps.into_iter().map(fake_ohlc).collect();
+                let result = OhlcSeries::try_from(ohlcs).map(|series| series.len());
+
+                assert_eq!(result, expected, "Test case '{}' failed", name);
+            }
+        }
+
+        #[test]
+        fn test_push() {
+            let mut series = fake_series(&[1000, 2000]);
+
+            assert_eq!(series.push(fake_ohlc(3000)), Ok(()));
+            assert_eq!(
+                series.push(fake_ohlc(2500)),
+                Err(SeriesError::Unsorted { index: 3 })
+            );
+            assert_eq!(series.timestamps(), &[1000, 2000, 3000]);
+        }
+
+        #[test]
+        fn test_timeframe() {
+            let series = fake_series(&[1625097600, 1625097660])
+                .with_timeframe(Timeframe::Minutes(1), Unit::Seconds);
+            let mut series = series.unwrap();
+
+            assert_eq!(series.timeframe(), Some(Timeframe::Minutes(1)));
+            assert_eq!(series.unit(), Some(Unit::Seconds));
+            assert_eq!(series.push(fake_ohlc(1625097720)), Ok(()));
+            assert_eq!(
+                series.push(fake_ohlc(1625097790)),
+                Err(SeriesError::Misaligned {
+                    index: 3,
+                    ts: 1625097790
+                })
+            );
+            assert_eq!(
+                fake_series(&[1625097600, 1625097630])
+                    .with_timeframe(Timeframe::Minutes(1), Unit::Seconds),
+                Err(SeriesError::Misaligned {
+                    index: 1,
+                    ts: 1625097630
+                })
+            );
+            assert_eq!(fake_series(&[1000]).timeframe(), None);
+        }
+
+        #[test]
+        fn test_columns() {
+            let series = fake_series(&[1000, 2000, 3000]);
+
+            assert_eq!(series.opens(), &[1000.0, 2000.0, 3000.0]);
+            assert_eq!(series.highs(), &[1010.0, 2010.0, 3010.0]);
+            assert_eq!(series.lows(), &[990.0, 1990.0, 2990.0]);
+            assert_eq!(series.closes(), &[1005.0, 2005.0, 3005.0]);
+            assert_eq!(
+                ohlc::closes(&series),
+                series.closes(),
+                "Free functions should work on a series"
+            );
+        }
+
+        #[test]
+        fn test_lookup() {
+            let series = fake_series(&[1000, 2000, 3000]);
+
+            assert_eq!(series.get_at(2000), Some(&fake_ohlc(2000)));
+            assert_eq!(series.get_at(2500), None);
+            assert_eq!(series.latest_at(2500), Some(&fake_ohlc(2000)));
+            assert_eq!(series.latest_at(999), None);
+            assert_eq!(series.search(2500), Err(2));
+        }
+
+        #[test]
+        fn test_filter() {
+            let timestamps = [1000, 2000, 3000, 4000];
+            let series = fake_series(&timestamps);
+            let test_cases = vec![
+                ("both_bounds_set", Some(1500), Some(3500)),
+                ("only_exclude_before", Some(2000), None),
+                ("only_exclude_after", None, Some(2500)),
+                ("no_bounds", None, None),
+                ("exclude_after_zero", Some(1500), Some(0)),
+            ];
+
+            for (name, before, after) in test_cases {
+                let opts = || Opts {
+                    exclude_before: before.map(Timestamp::from_secs),
+                    exclude_after: after.map(Timestamp::from_secs),
+                    unit: Unit::Seconds,
+                };
+                let expected = ohlc::filter(&series, opts());
+
+                assert_eq!(
+                    series.filter(opts()),
+                    expected,
+                    "Test case '{}' failed",
+                    name
+                );
+            }
+        }
+
+        #[test]
+        fn test_iter() {
+            let series = fake_series(&[1000, 2000]);
+            let timestamps: Vec<u64> = (&series).into_iter().map(|ohlc| ohlc.ts).collect();
+
+            assert_eq!(timestamps, vec![1000, 2000]);
+            assert_eq!(series.into_vec(), vec![fake_ohlc(1000), fake_ohlc(2000)]);
+        }
+    }
+}
@@ -0,0 +1,195 @@
+pub mod slippage {
+    use crate::models::models::Side;
+    use crate::ohlc::ohlc::OHLC;
+
+    /// Estimates how far a fill price moves against the trader in the OHLC it executes in.
+    pub trait SlippageModel {
+        /// Returns the slippage per unit for an order of `quantity` filling at `price`, as a
+        /// price amount against the trader.
+        fn slippage(&self, side: Side, price: f64, quantity: f64, ohlc: &OHLC) -> f64;
+    }
+
+    /// Returns the fill price after slippage, moved against the trader and never outside the
+    /// OHLC's low and high.
+    ///
+    /// Negative slippage is ignored, so the price never improves.
+    pub fn adjust<M: SlippageModel + ?Sized>(
+        model: &M,
+        side: Side,
+        price: f64,
+        quantity: f64,
+        ohlc: &OHLC,
+    ) -> f64 {
+        let slippage = model.slippage(side, price, quantity, ohlc);
+        let price = if slippage > 0.0 {
+            price + side.sign() * slippage
+        } else {
+            price
+        };
+
+        price.max(ohlc.low).min(ohlc.high)
+    }
+
+    /// No slippage.
+    #[derive(Debug, PartialEq, Clone, Copy, Default)]
+    pub struct NoSlippage;
+
+    impl SlippageModel for NoSlippage {
+        fn slippage(&self, _side: Side, _price: f64, _quantity: f64, _ohlc: &OHLC) -> f64 {
+            0.0
+        }
+    }
+
+    /// A fixed number of basis points of the price.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct FixedBps(pub f64);
+
+    impl SlippageModel for FixedBps {
+        fn slippage(&self, _side: Side, price: f64, _quantity: f64, _ohlc: &OHLC) -> f64 {
+            price * self.0 / 10_000.0
+        }
+    }
+
+    /// Crosses half of a fixed bid-ask spread, in price units.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct Spread(pub f64);
+
+    impl SlippageModel for Spread {
+        fn slippage(&self, _side: Side, _price: f64, _quantity: f64, _ohlc: &OHLC) -> f64 {
+            self.0 / 2.0
+        }
+    }
+
+    /// A fraction of the price for each unit of participation in the OHLC's volume.
+    ///
+    /// An `impact` of 0.1 moves the price 1% for an order of 10% of the volume. An OHLC without
+    /// volume moves the price to the worst of its range.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct VolumeParticipation {
+        pub impact: f64,
+    }
+
+    impl SlippageModel for VolumeParticipation {
+        fn slippage(&self, _side: Side, price: f64, quantity: f64, ohlc: &OHLC) -> f64 {
+            match participation(quantity, ohlc) {
+                Some(participation) => price * self.impact * participation,
+                None => f64::INFINITY,
+            }
+        }
+    }
+
+    /// Market impact that grows with the square root of participation in the OHLC's volume,
+    /// scaled by the OHLC's range as its volatility.
+    ///
+    /// Slippage is `coefficient * (high - low) * sqrt(quantity / volume)`. An OHLC without volume
+    /// moves the price to the worst of its range.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct SquareRoot {
+        pub coefficient: f64,
+    }
+
+    impl SlippageModel for SquareRoot {
+        fn slippage(&self, _side: Side, _price: f64, quantity: f64, ohlc: &OHLC) -> f64 {
+            match participation(quantity, ohlc) {
+                Some(participation) => {
+                    self.coefficient * (ohlc.high - ohlc.low) * participation.sqrt()
+                }
+                None => f64::INFINITY,
+            }
+        }
+    }
+
+    /// The fraction of the OHLC's volume an order is, or None if the OHLC has no volume.
+    fn participation(quantity: f64, ohlc: &OHLC) -> Option<f64> {
+        ohlc.vol
+            .filter(|&vol| vol > 0.0)
+            .map(|vol| quantity.abs() / vol)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const EPSILON: f64 = 1e-9;
+
+        type TestCase<'a> = (&'a str, Box<dyn SlippageModel>, Side, f64, f64);
+
+        #[test]
+        fn test_adjust() {
+            let ohlc = OHLC::new(100.0, 102.0, 97.0, 101.0, 1000).with_volume(10_000.0);
+
+            let test_cases: Vec<TestCase> = vec![
+                ("none", Box::new(NoSlippage), Side::Buy, 100.0, 100.0),
+                ("bps buy", Box::new(FixedBps(5.0)), Side::Buy, 100.0, 100.05),
+                (
+                    "bps sell",
+                    Box::new(FixedBps(5.0)),
+                    Side::Sell,
+                    100.0,
+                    99.95,
+                ),
+                ("spread", Box::new(Spread(0.1)), Side::Sell, 100.0, 99.95),
+                (
+                    "participation",
+                    Box::new(VolumeParticipation { impact: 0.1 }),
+                    Side::Buy,
+                    1000.0,
+                    101.0,
+                ),
+                (
+                    "square root",
+                    Box::new(SquareRoot { coefficient: 0.5 }),
+                    Side::Sell,
+                    2500.0,
+                    98.75,
+                ),
+                (
+                    "clamped to high",
+                    Box::new(FixedBps(500.0)),
+                    Side::Buy,
+                    100.0,
+                    102.0,
+                ),
+                (
+                    "clamped to low",
+                    Box::new(VolumeParticipation { impact: 10.0 }),
+                    Side::Sell,
+                    5000.0,
+                    97.0,
+                ),
+                (
+                    "no improvement",
+                    Box::new(FixedBps(-5.0)),
+                    Side::Buy,
+                    100.0,
+                    100.0,
+                ),
+            ];
+
+            for (name, model, side, quantity, expected) in test_cases {
+                let result = adjust(model.as_ref(), side, 100.0, quantity, &ohlc);
+
+                assert!(
+                    (result - expected).abs() < EPSILON,
+                    "Test case '{}' failed: expected {}, got {}",
+                    name,
+                    expected,
+                    result
+                );
+            }
+        }
+
+        #[test]
+        fn test_without_volume() {
+            let ohlc = OHLC::new(100.0, 102.0, 97.0, 101.0, 1000);
+            let model = SquareRoot { coefficient: 0.5 };
+
+            assert_eq!(adjust(&model, Side::Buy, 100.0, 1.0, &ohlc), 102.0);
+            assert_eq!(adjust(&model, Side::Sell, 100.0, 1.0, &ohlc), 97.0);
+            assert_eq!(
+                adjust(&model, Side::Buy, 100.0, 1.0, &ohlc.with_volume(0.0)),
+                102.0
+            );
+        }
+    }
+}
@@ -0,0 +1,729 @@
+pub mod streaming {
+    use crate::indicators::indicators::{
+        bands, cci_value, directional_index, directional_indicators, directional_move, ema_step,
+        gain_loss, mean, obv_step, percent_k, rsi_value, true_range, typical_price, weighted_mean,
+        wilder_step, wilder_sum_step, BandsPoint, DmiPoint, MacdPoint, StochasticPoint,
+    };
+    use crate::ohlc::ohlc::OHLC;
+    use std::collections::VecDeque;
+
+    /// The value produced by an indicator for a single OHLC.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub enum Output {
+        Value(f64),
+        Macd(MacdPoint),
+        Bands(BandsPoint),
+        Stochastic(StochasticPoint),
+        Dmi(DmiPoint),
+    }
+
+    impl Output {
+        /// Returns the value of a single valued output.
+        pub fn value(&self) -> Option<f64> {
+            match self {
+                Output::Value(value) => Some(*value),
+                _ => None,
+            }
+        }
+    }
+
+    /// An indicator updated one OHLC at a time.
+    ///
+    /// Each implementation returns exactly what the matching function in `indicators` returns for
+    /// the same OHLC, including `None` during warm up.
+    pub trait Indicator {
+        /// Add the next OHLC, returning the indicator value for it.
+        fn update(&mut self, ohlc: &OHLC) -> Option<Output>;
+
+        /// Discard all state, as if no OHLC had been seen.
+        fn reset(&mut self);
+    }
+
+    /// A fixed length window of the most recent values.
+    #[derive(Debug, Clone)]
+    struct Window<T> {
+        period: usize,
+        values: VecDeque<T>,
+    }
+
+    impl<T> Window<T> {
+        fn new(period: usize) -> Self {
+            Window {
+                period,
+                values: VecDeque::with_capacity(period),
+            }
+        }
+
+        /// Add a value, returning the full window once there are `period` values.
+        fn push(&mut self, value: T) -> Option<&[T]> {
+            if self.period == 0 {
+                return None;
+            }
+            if self.values.len() == self.period {
+                self.values.pop_front();
+            }
+            self.values.push_back(value);
+
+            if self.values.len() == self.period {
+                Some(self.values.make_contiguous())
+            } else {
+                None
+            }
+        }
+
+        fn clear(&mut self) {
+            self.values.clear();
+        }
+    }
+
+    /// Streaming simple moving average of closes.
+    #[derive(Debug, Clone)]
+    pub struct Sma {
+        window: Window<f64>,
+    }
+
+    impl Sma {
+        /// Return a new Sma.
+        pub fn new(period: usize) -> Self {
+            Sma {
+                window: Window::new(period),
+            }
+        }
+
+        /// Add the next value, returning the average.
+        pub fn update_value(&mut self, value: f64) -> Option<f64> {
+            self.window.push(value).map(mean)
+        }
+    }
+
+    impl Indicator for Sma {
+        fn update(&mut self, ohlc: &OHLC) -> Option<Output> {
+            self.update_value(ohlc.close).map(Output::Value)
+        }
+
+        fn reset(&mut self) {
+            self.window.clear();
+        }
+    }
+
+    /// Streaming exponential moving average of closes.
+    #[derive(Debug, Clone)]
+    pub struct Ema {
+        alpha: f64,
+        seed: Window<f64>,
+        value: Option<f64>,
+    }
+
+    impl Ema {
+        /// Return a new Ema.
+        pub fn new(period: usize) -> Self {
+            Ema {
+                alpha: 2.0 / (period as f64 + 1.0),
+                seed: Window::new(period),
+                value: None,
+            }
+        }
+
+        /// Add the next value, returning the average.
+        pub fn update_value(&mut self, value: f64) -> Option<f64> {
+            self.value = match self.value {
+                Some(prev) => Some(ema_step(prev, value, self.alpha)),
+                None => self.seed.push(value).map(mean),
+            };
+            self.value
+        }
+    }
+
+    impl Indicator for Ema {
+        fn update(&mut self, ohlc: &OHLC) -> Option<Output> {
+            self.update_value(ohlc.close).map(Output::Value)
+        }
+
+        fn reset(&mut self) {
+            self.seed.clear();
+            self.value = None;
+        }
+    }
+
+    /// Streaming linearly weighted moving average of closes.
+    #[derive(Debug, Clone)]
+    pub struct Wma {
+        window: Window<f64>,
+    }
+
+    impl Wma {
+        /// Return a new Wma.
+        pub fn new(period: usize) -> Self {
+            Wma {
+                window: Window::new(period),
+            }
+        }
+
+        /// Add the next value, returning the average.
+        pub fn update_value(&mut self, value: f64) -> Option<f64> {
+            self.window.push(value).map(weighted_mean)
+        }
+    }
+
+    impl Indicator for Wma {
+        fn update(&mut self, ohlc: &OHLC) -> Option<Output> {
+            self.update_value(ohlc.close).map(Output::Value)
+        }
+
+        fn reset(&mut self) {
+            self.window.clear();
+        }
+    }
+
+    /// Streaming Relative Strength Index of closes.
+    #[derive(Debug, Clone)]
+    pub struct Rsi {
+        period: usize,
+        prev: Option<f64>,
+        seed: Vec<(f64, f64)>,
+        averages: Option<(f64, f64)>,
+    }
+
+    impl Rsi {
+        /// Return a new Rsi.
+        pub fn new(period: usize) -> Self {
+            Rsi {
+                period,
+                prev: None,
+                seed: Vec::with_capacity(period),
+                averages: None,
+            }
+        }
+
+        /// Add the next value, returning the index.
+        pub fn update_value(&mut self, value: f64) -> Option<f64> {
+            let prev = self.prev.replace(value)?;
+            if self.period == 0 {
+                return None;
+            }
+
+            let (gain, loss) = gain_loss(value - prev);
+
+            let (avg_gain, avg_loss) = match self.averages {
+                Some((avg_gain, avg_loss)) => (
+                    wilder_step(avg_gain, gain, self.period),
+                    wilder_step(avg_loss, loss, self.period),
+                ),
+                None => {
+                    self.seed.push((gain, loss));
+                    if self.seed.len() < self.period {
+                        return None;
+                    }
+                    let period = self.period as f64;
+                    (
+                        self.seed.iter().map(|c| c.0).sum::<f64>() / period,
+                        self.seed.iter().map(|c| c.1).sum::<f64>() / period,
+                    )
+                }
+            };
+
+            self.averages = Some((avg_gain, avg_loss));
+            Some(rsi_value(avg_gain, avg_loss))
+        }
+    }
+
+    impl Indicator for Rsi {
+        fn update(&mut self, ohlc: &OHLC) -> Option<Output> {
+            self.update_value(ohlc.close).map(Output::Value)
+        }
+
+        fn reset(&mut self) {
+            self.prev = None;
+            self.seed.clear();
+            self.averages = None;
+        }
+    }
+
+    /// Streaming Moving Average Convergence Divergence of closes.
+    #[derive(Debug, Clone)]
+    pub struct Macd {
+        fast: Ema,
+        slow: Ema,
+        signal: Ema,
+    }
+
+    impl Macd {
+        /// Return a new Macd.
+        pub fn new(fast: usize, slow: usize, signal: usize) -> Self {
+            Macd {
+                fast: Ema::new(fast),
+                slow: Ema::new(slow),
+                signal: Ema::new(signal),
+            }
+        }
+
+        /// Add the next value, returning the MACD.
+        pub fn update_value(&mut self, value: f64) -> Option<MacdPoint> {
+            let fast = self.fast.update_value(value);
+            let slow = self.slow.update_value(value);
+            let line = fast? - slow?;
+            let signal = self.signal.update_value(line);
+
+            Some(MacdPoint {
+                macd: line,
+                signal,
+                histogram: signal.map(|s| line - s),
+            })
+        }
+    }
+
+    impl Indicator for Macd {
+        fn update(&mut self, ohlc: &OHLC) -> Option<Output> {
+            self.update_value(ohlc.close).map(Output::Macd)
+        }
+
+        fn reset(&mut self) {
+            self.fast.reset();
+            self.slow.reset();
+            self.signal.reset();
+        }
+    }
+
+    /// Streaming Bollinger Bands of closes.
+    #[derive(Debug, Clone)]
+    pub struct Bollinger {
+        window: Window<f64>,
+        k: f64,
+    }
+
+    impl Bollinger {
+        /// Return a new Bollinger.
+        pub fn new(period: usize, k: f64) -> Self {
+            Bollinger {
+                window: Window::new(period),
+                k,
+            }
+        }
+
+        /// Add the next value, returning the bands.
+        pub fn update_value(&mut self, value: f64) -> Option<BandsPoint> {
+            let k = self.k;
+            self.window.push(value).map(|w| bands(w, k))
+        }
+    }
+
+    impl Indicator for Bollinger {
+        fn update(&mut self, ohlc: &OHLC) -> Option<Output> {
+            self.update_value(ohlc.close).map(Output::Bands)
+        }
+
+        fn reset(&mut self) {
+            self.window.clear();
+        }
+    }
+
+    /// Streaming Average True Range.
+    #[derive(Debug, Clone)]
+    pub struct Atr {
+        period: usize,
+        prev: Option<OHLC>,
+        seed: Vec<f64>,
+        value: Option<f64>,
+    }
+
+    impl Atr {
+        /// Return a new Atr.
+        pub fn new(period: usize) -> Self {
+            Atr {
+                period,
+                prev: None,
+                seed: Vec::with_capacity(period),
+                value: None,
+            }
+        }
+    }
+
+    impl Indicator for Atr {
+        fn update(&mut self, ohlc: &OHLC) -> Option<Output> {
+            let prev = self.prev.replace(ohlc.clone())?;
+            if self.period == 0 {
+                return None;
+            }
+
+            let range = true_range(ohlc, &prev);
+
+            self.value = match self.value {
+                Some(value) => Some(wilder_step(value, range, self.period)),
+                None => {
+                    self.seed.push(range);
+                    if self.seed.len() < self.period {
+                        return None;
+                    }
+                    Some(mean(&self.seed))
+                }
+            };
+
+            self.value.map(Output::Value)
+        }
+
+        fn reset(&mut self) {
+            self.prev = None;
+            self.seed.clear();
+            self.value = None;
+        }
+    }
+
+    /// Streaming Stochastic Oscillator.
+    #[derive(Debug, Clone)]
+    pub struct Stochastic {
+        window: Window<OHLC>,
+        d: Sma,
+    }
+
+    impl Stochastic {
+        /// Return a new Stochastic.
+        pub fn new(k_period: usize, d_period: usize) -> Self {
+            Stochastic {
+                window: Window::new(k_period),
+                d: Sma::new(d_period),
+            }
+        }
+    }
+
+    impl Indicator for Stochastic {
+        fn update(&mut self, ohlc: &OHLC) -> Option<Output> {
+            let k = self.window.push(ohlc.clone()).map(percent_k)?;
+            let d = self.d.update_value(k);
+
+            Some(Output::Stochastic(StochasticPoint { k, d }))
+        }
+
+        fn reset(&mut self) {
+            self.window.clear();
+            self.d.reset();
+        }
+    }
+
+    /// Streaming Average Directional Index.
+    #[derive(Debug, Clone)]
+    pub struct Adx {
+        period: usize,
+        prev: Option<OHLC>,
+        seed: Vec<(f64, f64, f64)>,
+        sums: Option<(f64, f64, f64)>,
+        dxs: Vec<f64>,
+        adx: Option<f64>,
+    }
+
+    impl Adx {
+        /// Return a new Adx.
+        pub fn new(period: usize) -> Self {
+            Adx {
+                period,
+                prev: None,
+                seed: Vec::with_capacity(period),
+                sums: None,
+                dxs: Vec::with_capacity(period),
+                adx: None,
+            }
+        }
+    }
+
+    impl Indicator for Adx {
+        fn update(&mut self, ohlc: &OHLC) -> Option<Output> {
+            let prev = self.prev.replace(ohlc.clone())?;
+            if self.period == 0 {
+                return None;
+            }
+
+            let (p, m, r) = directional_move(ohlc, &prev);
+
+            let (plus, minus, range) = match self.sums {
+                Some((plus, minus, range)) => (
+                    wilder_sum_step(plus, p, self.period),
+                    wilder_sum_step(minus, m, self.period),
+                    wilder_sum_step(range, r, self.period),
+                ),
+                None => {
+                    self.seed.push((p, m, r));
+                    if self.seed.len() < self.period {
+                        return None;
+                    }
+                    (
+                        self.seed.iter().map(|m| m.0).sum::<f64>(),
+                        self.seed.iter().map(|m| m.1).sum::<f64>(),
+                        self.seed.iter().map(|m| m.2).sum::<f64>(),
+                    )
+                }
+            };
+            self.sums = Some((plus, minus, range));
+
+            let (plus_di, minus_di) = directional_indicators(plus, minus, range);
+            let dx = directional_index(plus_di, minus_di);
+
+            self.adx = match self.adx {
+                Some(prev) => Some(wilder_step(prev, dx, self.period)),
+                None => {
+                    self.dxs.push(dx);
+                    if self.dxs.len() == self.period {
+                        Some(mean(&self.dxs))
+                    } else {
+                        None
+                    }
+                }
+            };
+
+            Some(Output::Dmi(DmiPoint {
+                plus_di,
+                minus_di,
+                adx: self.adx,
+            }))
+        }
+
+        fn reset(&mut self) {
+            self.prev = None;
+            self.seed.clear();
+            self.sums = None;
+            self.dxs.clear();
+            self.adx = None;
+        }
+    }
+
+    /// Streaming Commodity Channel Index.
+    #[derive(Debug, Clone)]
+    pub struct Cci {
+        window: Window<f64>,
+    }
+
+    impl Cci {
+        /// Return a new Cci.
+        pub fn new(period: usize) -> Self {
+            Cci {
+                window: Window::new(period),
+            }
+        }
+    }
+
+    impl Indicator for Cci {
+        fn update(&mut self, ohlc: &OHLC) -> Option<Output> {
+            self.window
+                .push(typical_price(ohlc))
+                .map(cci_value)
+                .map(Output::Value)
+        }
+
+        fn reset(&mut self) {
+            self.window.clear();
+        }
+    }
+
+    /// Streaming On Balance Volume.
+    #[derive(Debug, Clone, Default)]
+    pub struct Obv {
+        prev: Option<OHLC>,
+        total: f64,
+    }
+
+    impl Obv {
+        /// Return a new Obv.
+        pub fn new() -> Self {
+            Self::default()
+        }
+    }
+
+    impl Indicator for Obv {
+        fn update(&mut self, ohlc: &OHLC) -> Option<Output> {
+            if let Some(prev) = self.prev.replace(ohlc.clone()) {
+                self.total = obv_step(self.total, &prev, ohlc);
+            }
+            Some(Output::Value(self.total))
+        }
+
+        fn reset(&mut self) {
+            self.prev = None;
+            self.total = 0.0;
+        }
+    }
+
+    /// Streaming Volume Weighted Average Price.
+    #[derive(Debug, Clone, Default)]
+    pub struct Vwap {
+        value: f64,
+        volume: f64,
+    }
+
+    impl Vwap {
+        /// Return a new Vwap.
+        pub fn new() -> Self {
+            Self::default()
+        }
+    }
+
+    impl Indicator for Vwap {
+        fn update(&mut self, ohlc: &OHLC) -> Option<Output> {
+            let vol = ohlc.vol.unwrap_or(0.0);
+            self.value += typical_price(ohlc) * vol;
+            self.volume += vol;
+
+            if self.volume > 0.0 {
+                Some(Output::Value(self.value / self.volume))
+            } else {
+                None
+            }
+        }
+
+        fn reset(&mut self) {
+            self.value = 0.0;
+            self.volume = 0.0;
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::indicators::indicators;
+        use crate::ohlc::ohlc::closes;
+
+        // A deterministic, noisy walk long enough to warm up every indicator.
+        fn fixture() -> Vec<OHLC> {
+            let mut close: f64 = 100.0;
+            (0..60)
+                .map(|i| {
+                    let open = close;
+                    let step = ((i * 37 % 17) as f64 - 8.0) * 0.25;
+                    close = open + step;
+                    let high = open.max(close) + (i % 3) as f64 * 0.5;
+                    let low = open.min(close) - (i % 4) as f64 * 0.25;
+                    let ohlc = OHLC::new(open, high, low, close, 1625097600 + 60 * i);
+                    if i % 5 == 0 {
+                        ohlc
+                    } else {
+                        ohlc.with_volume(100.0 + (i * 13 % 7) as f64 * 10.0)
+                    }
+                })
+                .collect()
+        }
+
+        type TestCase = (&'static str, Box<dyn Indicator>, Vec<Option<Output>>);
+
+        fn run(indicator: &mut dyn Indicator, ohlcs: &[OHLC]) -> Vec<Option<Output>> {
+            ohlcs.iter().map(|ohlc| indicator.update(ohlc)).collect()
+        }
+
+        fn values(batch: Vec<Option<f64>>) -> Vec<Option<Output>> {
+            batch.into_iter().map(|v| v.map(Output::Value)).collect()
+        }
+
+        #[test]
+        fn test_matches_batch() {
+            let ohlcs = fixture();
+            let closes = closes(&ohlcs);
+
+            let test_cases: Vec<TestCase> = vec![
+                (
+                    "sma",
+                    Box::new(Sma::new(5)),
+                    values(indicators::sma(&closes, 5)),
+                ),
+                (
+                    "ema",
+                    Box::new(Ema::new(5)),
+                    values(indicators::ema(&closes, 5)),
+                ),
+                (
+                    "wma",
+                    Box::new(Wma::new(5)),
+                    values(indicators::wma(&closes, 5)),
+                ),
+                (
+                    "rsi",
+                    Box::new(Rsi::new(14)),
+                    values(indicators::rsi(&closes, 14)),
+                ),
+                (
+                    "macd",
+                    Box::new(Macd::new(12, 26, 9)),
+                    indicators::macd(&closes, 12, 26, 9)
+                        .into_iter()
+                        .map(|v| v.map(Output::Macd))
+                        .collect(),
+                ),
+                (
+                    "bollinger",
+                    Box::new(Bollinger::new(20, 2.0)),
+                    indicators::bollinger(&closes, 20, 2.0)
+                        .into_iter()
+                        .map(|v| v.map(Output::Bands))
+                        .collect(),
+                ),
+                (
+                    "atr",
+                    Box::new(Atr::new(14)),
+                    values(indicators::atr(&ohlcs, 14)),
+                ),
+                (
+                    "stochastic",
+                    Box::new(Stochastic::new(14, 3)),
+                    indicators::stochastic(&ohlcs, 14, 3)
+                        .into_iter()
+                        .map(|v| v.map(Output::Stochastic))
+                        .collect(),
+                ),
+                (
+                    "adx",
+                    Box::new(Adx::new(14)),
+                    indicators::adx(&ohlcs, 14)
+                        .into_iter()
+                        .map(|v| v.map(Output::Dmi))
+                        .collect(),
+                ),
+                (
+                    "cci",
+                    Box::new(Cci::new(20)),
+                    values(indicators::cci(&ohlcs, 20)),
+                ),
+                (
+                    "obv",
+                    Box::new(Obv::new()),
+                    indicators::obv(&ohlcs)
+                        .into_iter()
+                        .map(|v| Some(Output::Value(v)))
+                        .collect(),
+                ),
+                (
+                    "vwap",
+                    Box::new(Vwap::new()),
+                    values(indicators::vwap(&ohlcs)),
+                ),
+            ];
+
+            for (name, mut indicator, expected) in test_cases {
+                let result = run(indicator.as_mut(), &ohlcs);
+                assert_eq!(result, expected, "Test case '{}' failed", name);
+
+                indicator.reset();
+                let result = run(indicator.as_mut(), &ohlcs);
+                assert_eq!(result, expected, "Test case '{}' failed after reset", name);
+            }
+        }
+
+        #[test]
+        fn test_zero_period() {
+            let ohlcs = fixture();
+            let mut indicators: Vec<Box<dyn Indicator>> = vec![
+                Box::new(Sma::new(0)),
+                Box::new(Rsi::new(0)),
+                Box::new(Atr::new(0)),
+                Box::new(Adx::new(0)),
+            ];
+
+            for indicator in indicators.iter_mut() {
+                assert!(run(indicator.as_mut(), &ohlcs).iter().all(Option::is_none));
+            }
+        }
+
+        #[test]
+        fn test_output_value() {
+            assert_eq!(Output::Value(1.5).value(), Some(1.5));
+            assert_eq!(
+                Output::Stochastic(StochasticPoint { k: 1.0, d: None }).value(),
+                None
+            );
+        }
+    }
+}
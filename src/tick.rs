@@ -0,0 +1,303 @@
+pub mod tick {
+    use crate::ohlc::ohlc::OHLC;
+
+    /// The side of a trade or order. For a trade this is the aggressor.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum Side {
+        Buy,
+        Sell,
+    }
+
+    impl Side {
+        /// Returns 1 for a buy and -1 for a sell.
+        pub fn sign(&self) -> f64 {
+            match self {
+                Side::Buy => 1.0,
+                Side::Sell => -1.0,
+            }
+        }
+
+        /// Returns the other side.
+        pub fn opposite(&self) -> Side {
+            match self {
+                Side::Buy => Side::Sell,
+                Side::Sell => Side::Buy,
+            }
+        }
+    }
+
+    /// A single trade.
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct Trade {
+        pub price: f64,
+        pub size: f64,
+        pub side: Option<Side>,
+        pub ts: u64,
+        pub id: Option<String>,
+    }
+
+    /// Feeds often call trades ticks.
+    pub type Tick = Trade;
+
+    impl Trade {
+        /// Return a new Trade.
+        pub fn new(price: f64, size: f64, ts: u64) -> Self {
+            Trade {
+                price,
+                size,
+                side: None,
+                ts,
+                id: None,
+            }
+        }
+
+        /// Specify the aggressor side for a Trade.
+        pub fn with_side(mut self, side: Side) -> Self {
+            self.side = Some(side);
+            self
+        }
+
+        /// Specify the exchange trade id for a Trade.
+        pub fn with_id(mut self, id: impl Into<String>) -> Self {
+            self.id = Some(id.into());
+            self
+        }
+
+        /// Returns the traded value, price multiplied by size.
+        pub fn value(&self) -> f64 {
+            self.price * self.size
+        }
+    }
+
+    /// When a bar being built is complete.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub enum Threshold {
+        /// Fixed width time buckets, in the same units as the trade timestamps.
+        Time(u64),
+        /// A number of trades.
+        Ticks(u64),
+        /// Total traded size.
+        Volume(f64),
+        /// Total traded value, price multiplied by size.
+        Value(f64),
+    }
+
+    /// Builds OHLC from a stream of trades.
+    ///
+    /// Time bars are stamped with the start of their bucket. All other bars are stamped with the
+    /// timestamp of their first trade. Trades are never split across bars, so volume and value bars
+    /// can overshoot their threshold.
+    #[derive(Debug, Clone)]
+    pub struct BarBuilder {
+        threshold: Threshold,
+        current: Option<OHLC>,
+        ticks: u64,
+        volume: f64,
+        value: f64,
+    }
+
+    impl BarBuilder {
+        /// Return a new BarBuilder.
+        pub fn new(threshold: Threshold) -> Self {
+            BarBuilder {
+                threshold,
+                current: None,
+                ticks: 0,
+                volume: 0.0,
+                value: 0.0,
+            }
+        }
+
+        /// Add a trade, returning a bar if one was completed.
+        ///
+        /// A time bar is completed by the first trade of the following bucket, so the returned bar
+        /// never contains the trade being pushed.
+        pub fn push(&mut self, trade: &Trade) -> Option<OHLC> {
+            if let Threshold::Time(width) = self.threshold {
+                let start = bucket_start(trade.ts, width);
+                let completed = match &self.current {
+                    Some(bar) if bar.ts != start => self.flush(),
+                    _ => None,
+                };
+                self.add(trade, start);
+                return completed;
+            }
+
+            self.add(trade, trade.ts);
+
+            let done = match self.threshold {
+                Threshold::Ticks(n) => self.ticks >= n,
+                Threshold::Volume(v) => self.volume >= v,
+                Threshold::Value(v) => self.value >= v,
+                Threshold::Time(_) => false,
+            };
+
+            if done {
+                self.flush()
+            } else {
+                None
+            }
+        }
+
+        /// Returns the bar being built, even if incomplete, and resets the builder.
+        pub fn flush(&mut self) -> Option<OHLC> {
+            self.ticks = 0;
+            self.volume = 0.0;
+            self.value = 0.0;
+            self.current.take()
+        }
+
+        fn add(&mut self, trade: &Trade, ts: u64) {
+            match self.current.as_mut() {
+                Some(bar) => {
+                    bar.high = bar.high.max(trade.price);
+                    bar.low = bar.low.min(trade.price);
+                    bar.close = trade.price;
+                }
+                None => {
+                    self.current = Some(OHLC::new(
+                        trade.price,
+                        trade.price,
+                        trade.price,
+                        trade.price,
+                        ts,
+                    ));
+                }
+            }
+
+            self.ticks += 1;
+            self.volume += trade.size;
+            self.value += trade.value();
+
+            if let Some(bar) = self.current.as_mut() {
+                bar.vol = Some(self.volume);
+            }
+        }
+    }
+
+    /// Builds OHLC from a slice of trades, including the final incomplete bar.
+    pub fn bars(trades: &[Trade], threshold: Threshold) -> Vec<OHLC> {
+        let mut builder = BarBuilder::new(threshold);
+        let mut bars: Vec<OHLC> = trades
+            .iter()
+            .filter_map(|trade| builder.push(trade))
+            .collect();
+        bars.extend(builder.flush());
+        bars
+    }
+
+    /// A zero width puts each distinct timestamp in its own bucket.
+    fn bucket_start(ts: u64, width: u64) -> u64 {
+        let width = width.max(1);
+        ts - ts % width
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn trades() -> Vec<Trade> {
+            vec![
+                Trade::new(10.0, 1.0, 1000).with_side(Side::Buy),
+                Trade::new(12.0, 2.0, 1010).with_side(Side::Buy),
+                Trade::new(9.0, 1.0, 1059).with_side(Side::Sell),
+                Trade::new(11.0, 3.0, 1060),
+                Trade::new(8.0, 2.0, 1130).with_id("t5"),
+            ]
+        }
+
+        #[test]
+        fn test_trade() {
+            let trade = Trade::new(10.0, 2.5, 1625097600000)
+                .with_side(Side::Sell)
+                .with_id("abc");
+            let expected = Trade {
+                price: 10.0,
+                size: 2.5,
+                side: Some(Side::Sell),
+                ts: 1625097600000,
+                id: Some("abc".to_string()),
+            };
+
+            assert_eq!(trade, expected, "Trade struct should match expected values");
+            assert_eq!(trade.value(), 25.0, "Value should be price times size");
+        }
+
+        #[test]
+        fn test_bars() {
+            let test_cases = vec![
+                (
+                    "time",
+                    Threshold::Time(60),
+                    vec![
+                        OHLC::new(10.0, 12.0, 10.0, 12.0, 960).with_volume(3.0),
+                        OHLC::new(9.0, 11.0, 9.0, 11.0, 1020).with_volume(4.0),
+                        OHLC::new(8.0, 8.0, 8.0, 8.0, 1080).with_volume(2.0),
+                    ],
+                ),
+                (
+                    "ticks",
+                    Threshold::Ticks(2),
+                    vec![
+                        OHLC::new(10.0, 12.0, 10.0, 12.0, 1000).with_volume(3.0),
+                        OHLC::new(9.0, 11.0, 9.0, 11.0, 1059).with_volume(4.0),
+                        OHLC::new(8.0, 8.0, 8.0, 8.0, 1130).with_volume(2.0),
+                    ],
+                ),
+                (
+                    "volume",
+                    Threshold::Volume(4.0),
+                    vec![
+                        OHLC::new(10.0, 12.0, 9.0, 9.0, 1000).with_volume(4.0),
+                        OHLC::new(11.0, 11.0, 8.0, 8.0, 1060).with_volume(5.0),
+                    ],
+                ),
+                (
+                    "value",
+                    Threshold::Value(40.0),
+                    vec![
+                        OHLC::new(10.0, 12.0, 9.0, 9.0, 1000).with_volume(4.0),
+                        OHLC::new(11.0, 11.0, 8.0, 8.0, 1060).with_volume(5.0),
+                    ],
+                ),
+            ];
+
+            for (name, threshold, expected) in test_cases {
+                let result = bars(&trades(), threshold);
+
+                assert_eq!(result, expected, "Test case '{}' failed", name);
+            }
+        }
+
+        #[test]
+        fn test_bars_empty() {
+            let result = bars(&[], Threshold::Ticks(2));
+
+            assert_eq!(result, vec![], "Bars of no trades should be empty");
+        }
+
+        #[test]
+        fn test_push() {
+            let mut builder = BarBuilder::new(Threshold::Time(60));
+            let trades = trades();
+
+            assert_eq!(builder.push(&trades[0]), None);
+            assert_eq!(builder.push(&trades[1]), None);
+            assert_eq!(
+                builder.push(&trades[2]),
+                Some(OHLC::new(10.0, 12.0, 10.0, 12.0, 960).with_volume(3.0)),
+                "First trade of the next bucket should complete the bar"
+            );
+            assert_eq!(builder.push(&trades[3]), None);
+            assert_eq!(
+                builder.flush(),
+                Some(OHLC::new(9.0, 11.0, 9.0, 11.0, 1020).with_volume(4.0))
+            );
+            assert_eq!(
+                builder.flush(),
+                None,
+                "Flushing twice should return nothing"
+            );
+        }
+    }
+}
@@ -0,0 +1,458 @@
+pub mod timeframe {
+    use crate::ohlc::ohlc::OHLC;
+    use crate::timestamp::timestamp::Unit;
+    use std::fmt;
+    use std::str::FromStr;
+
+    pub(crate) const SECONDS_PER_DAY: u64 = 86_400;
+
+    /// The period covered by each OHLC.
+    ///
+    /// Time based timeframes are aligned to the Unix epoch in UTC, weeks start on Monday, and
+    /// months are counted from January 1970. Tick and volume timeframes have no alignment.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum Timeframe {
+        Seconds(u64),
+        Minutes(u64),
+        Hours(u64),
+        Days(u64),
+        Weeks(u64),
+        Months(u64),
+        /// A number of trades per OHLC.
+        Ticks(u64),
+        /// An amount of volume per OHLC.
+        Volume(f64),
+    }
+
+    /// Errors parsing or checking against a Timeframe.
+    #[derive(Debug, PartialEq, Eq, Clone)]
+    pub enum TimeframeError {
+        /// The string is not a count followed by a known suffix.
+        Invalid { input: String },
+        /// The OHLC at `index` does not start on a timeframe boundary.
+        Misaligned { index: usize, ts: u64 },
+    }
+
+    impl fmt::Display for TimeframeError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                TimeframeError::Invalid { input } => write!(f, "Invalid timeframe '{}'", input),
+                TimeframeError::Misaligned { index, ts } => {
+                    write!(
+                        f,
+                        "OHLC at index {} has timestamp {} not aligned to the timeframe",
+                        index, ts
+                    )
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for TimeframeError {}
+
+    impl Timeframe {
+        /// Returns the start of the bucket containing a timestamp.
+        ///
+        /// Returns None for tick and volume timeframes. Buckets that would start before the
+        /// epoch are clamped to it.
+        pub fn bucket_start(&self, ts: u64, unit: Unit) -> Option<u64> {
+            self.bucket(ts, unit).map(|(start, _)| start)
+        }
+
+        /// Returns the start of the bucket after the one containing a timestamp.
+        ///
+        /// Returns None for tick and volume timeframes.
+        pub fn next_start(&self, ts: u64, unit: Unit) -> Option<u64> {
+            self.bucket(ts, unit).map(|(_, next)| next)
+        }
+
+        /// The start of the bucket containing a timestamp and the start of the next one.
+        fn bucket(&self, ts: u64, unit: Unit) -> Option<(u64, u64)> {
+            let per_day = SECONDS_PER_DAY * unit.per_second();
+
+            let bounds = match *self {
+                Timeframe::Seconds(_)
+                | Timeframe::Minutes(_)
+                | Timeframe::Hours(_)
+                | Timeframe::Days(_) => {
+                    let width = self.width(unit)?;
+                    let start = ts - ts % width;
+                    (start, start.saturating_add(width))
+                }
+                Timeframe::Weeks(n) => {
+                    let n = n.max(1) as i64;
+                    let days = week_start((ts / per_day) as i64, n);
+                    (
+                        days.max(0) as u64 * per_day,
+                        (days + n * 7) as u64 * per_day,
+                    )
+                }
+                Timeframe::Months(n) => {
+                    let n = n.max(1) as i64;
+                    let months = month_number((ts / per_day) as i64).div_euclid(n) * n;
+                    let start = |months: i64| month_start(months) as u64 * per_day;
+                    (start(months), start(months + n))
+                }
+                Timeframe::Ticks(_) | Timeframe::Volume(_) => return None,
+            };
+
+            Some(bounds)
+        }
+
+        /// The length of every bucket, or None if it varies or is not measured in time.
+        fn width(&self, unit: Unit) -> Option<u64> {
+            let seconds = match *self {
+                Timeframe::Seconds(n) => n,
+                Timeframe::Minutes(n) => n.saturating_mul(60),
+                Timeframe::Hours(n) => n.saturating_mul(3_600),
+                Timeframe::Days(n) => n.saturating_mul(SECONDS_PER_DAY),
+                Timeframe::Weeks(n) => n.max(1).saturating_mul(7 * SECONDS_PER_DAY),
+                Timeframe::Months(_) | Timeframe::Ticks(_) | Timeframe::Volume(_) => return None,
+            };
+            Some(seconds.saturating_mul(unit.per_second()).max(1))
+        }
+
+        /// The number of buckets from the one starting at `from` up to the one starting at `to`,
+        /// counted without stepping through them.
+        ///
+        /// Returns None for tick and volume timeframes.
+        pub(crate) fn buckets_between(&self, from: u64, to: u64, unit: Unit) -> Option<u64> {
+            if let Some(width) = self.width(unit) {
+                return Some(to.saturating_sub(from) / width);
+            }
+
+            let Timeframe::Months(n) = *self else {
+                return None;
+            };
+            let per_day = SECONDS_PER_DAY * unit.per_second();
+            let month = |ts: u64| month_number((ts / per_day) as i64);
+            Some(((month(to) - month(from)).max(0) / n.max(1) as i64) as u64)
+        }
+
+        /// Returns true if a timestamp falls on a bucket start. Always true for tick and volume
+        /// timeframes.
+        pub fn is_aligned(&self, ts: u64, unit: Unit) -> bool {
+            self.bucket_start(ts, unit).is_none_or(|start| start == ts)
+        }
+
+        /// Returns true if the timeframe is measured in time rather than trades or volume.
+        pub fn is_time_based(&self) -> bool {
+            !matches!(self, Timeframe::Ticks(_) | Timeframe::Volume(_))
+        }
+    }
+
+    impl fmt::Display for Timeframe {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Timeframe::Seconds(n) => write!(f, "{}s", n),
+                Timeframe::Minutes(n) => write!(f, "{}m", n),
+                Timeframe::Hours(n) => write!(f, "{}h", n),
+                Timeframe::Days(n) => write!(f, "{}D", n),
+                Timeframe::Weeks(n) => write!(f, "{}W", n),
+                Timeframe::Months(n) => write!(f, "{}M", n),
+                Timeframe::Ticks(n) => write!(f, "{}t", n),
+                Timeframe::Volume(v) => write!(f, "{}v", v),
+            }
+        }
+    }
+
+    impl FromStr for Timeframe {
+        type Err = TimeframeError;
+
+        /// Parses a count followed by a suffix, such as "5m", "1h" or "1D".
+        ///
+        /// Suffixes are `s`, `m` (minutes), `h`, `d`, `w`, `M` (months), `t` (ticks) and `v`
+        /// (volume). Only `m` and `M` are case sensitive. The count must be positive.
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let invalid = || TimeframeError::Invalid {
+                input: s.to_string(),
+            };
+
+            let s = s.trim();
+            let suffix = s.chars().last().ok_or_else(invalid)?;
+            let count = &s[..s.len() - suffix.len_utf8()];
+
+            if matches!(suffix, 'v' | 'V') {
+                let volume: f64 = count.parse().map_err(|_| invalid())?;
+                if !volume.is_finite() || volume <= 0.0 {
+                    return Err(invalid());
+                }
+                return Ok(Timeframe::Volume(volume));
+            }
+
+            if !count.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            let n: u64 = count.parse().map_err(|_| invalid())?;
+            if n == 0 {
+                return Err(invalid());
+            }
+
+            match suffix {
+                's' | 'S' => Ok(Timeframe::Seconds(n)),
+                'm' => Ok(Timeframe::Minutes(n)),
+                'h' | 'H' => Ok(Timeframe::Hours(n)),
+                'd' | 'D' => Ok(Timeframe::Days(n)),
+                'w' | 'W' => Ok(Timeframe::Weeks(n)),
+                'M' => Ok(Timeframe::Months(n)),
+                't' | 'T' => Ok(Timeframe::Ticks(n)),
+                _ => Err(invalid()),
+            }
+        }
+    }
+
+    /// Checks each OHLC timestamp falls on a bucket start, reporting every misaligned OHLC.
+    pub fn validate(
+        ohlcs: &[OHLC],
+        timeframe: Timeframe,
+        unit: Unit,
+    ) -> Result<(), Vec<TimeframeError>> {
+        let errors: Vec<TimeframeError> = ohlcs
+            .iter()
+            .enumerate()
+            .filter(|(_, ohlc)| !timeframe.is_aligned(ohlc.ts, unit))
+            .map(|(index, ohlc)| TimeframeError::Misaligned { index, ts: ohlc.ts })
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Day number of the start of the `weeks` long period containing a day, counting periods
+    /// from the Monday before 1970-01-01.
+    pub(crate) fn week_start(days: i64, weeks: i64) -> i64 {
+        let width = weeks * 7;
+        // 1970-01-01 was a Thursday, three days after a Monday.
+        (days + 3).div_euclid(width) * width - 3
+    }
+
+    /// Months since January 1970 of the month containing a day.
+    pub(crate) fn month_number(days: i64) -> i64 {
+        let (y, m, _) = civil_from_days(days);
+        (y - 1970) * 12 + m - 1
+    }
+
+    /// Day number of the first day of a month counted from January 1970.
+    pub(crate) fn month_start(months: i64) -> i64 {
+        days_from_civil(1970 + months.div_euclid(12), months.rem_euclid(12) + 1, 1)
+    }
+
+    /// Days since 1970-01-01 for a proleptic Gregorian date.
+    pub(crate) fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+        let y = if m <= 2 { y - 1 } else { y };
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let mp = (m + 9) % 12;
+        let doy = (153 * mp + 2) / 5 + d - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    /// Proleptic Gregorian (year, month, day) for a number of days since 1970-01-01.
+    pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let d = doy - (153 * mp + 2) / 5 + 1;
+        let m = if mp < 10 { mp + 3 } else { mp - 9 };
+        let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+        (y, m, d)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_parse() {
+            let test_cases = vec![
+                ("30s", Ok(Timeframe::Seconds(30))),
+                ("5m", Ok(Timeframe::Minutes(5))),
+                ("1h", Ok(Timeframe::Hours(1))),
+                ("4H", Ok(Timeframe::Hours(4))),
+                ("1D", Ok(Timeframe::Days(1))),
+                ("1d", Ok(Timeframe::Days(1))),
+                ("2W", Ok(Timeframe::Weeks(2))),
+                ("3M", Ok(Timeframe::Months(3))),
+                ("100t", Ok(Timeframe::Ticks(100))),
+                ("2500.5v", Ok(Timeframe::Volume(2500.5))),
+                (" 15m ", Ok(Timeframe::Minutes(15))),
+                ("", Err(())),
+                ("m", Err(())),
+                ("0m", Err(())),
+                ("-5m", Err(())),
+                ("5x", Err(())),
+                ("5", Err(())),
+                ("0v", Err(())),
+            ];
+
+            for (input, expected) in test_cases {
+                let result = input.parse::<Timeframe>().map_err(|_| ());
+
+                assert_eq!(result, expected, "Test case '{}' failed", input);
+            }
+        }
+
+        #[test]
+        fn test_display_round_trip() {
+            for input in ["30s", "5m", "1h", "1D", "2W", "3M", "100t", "2500.5v"] {
+                let timeframe: Timeframe = input.parse().unwrap();
+
+                assert_eq!(timeframe.to_string(), input);
+            }
+        }
+
+        #[test]
+        fn test_bucket_start() {
+            // 2021-07-01 13:47:12 UTC, a Thursday.
+            let ts = 1625147232;
+
+            let test_cases = vec![
+                (
+                    "5m",
+                    Timeframe::Minutes(5),
+                    Unit::Seconds,
+                    ts,
+                    Some(1625147100),
+                ),
+                (
+                    "1h",
+                    Timeframe::Hours(1),
+                    Unit::Seconds,
+                    ts,
+                    Some(1625144400),
+                ),
+                (
+                    "1D",
+                    Timeframe::Days(1),
+                    Unit::Seconds,
+                    ts,
+                    Some(1625097600),
+                ),
+                (
+                    "1W",
+                    Timeframe::Weeks(1),
+                    Unit::Seconds,
+                    ts,
+                    Some(1624838400),
+                ),
+                (
+                    "1M",
+                    Timeframe::Months(1),
+                    Unit::Seconds,
+                    ts,
+                    Some(1625097600),
+                ),
+                (
+                    "3M",
+                    Timeframe::Months(3),
+                    Unit::Seconds,
+                    ts,
+                    Some(1625097600),
+                ),
+                (
+                    "12M",
+                    Timeframe::Months(12),
+                    Unit::Seconds,
+                    ts,
+                    Some(1609459200),
+                ),
+                (
+                    "1h in millis",
+                    Timeframe::Hours(1),
+                    Unit::Millis,
+                    ts * 1000 + 1,
+                    Some(1625144400000),
+                ),
+                (
+                    "1M in millis",
+                    Timeframe::Months(1),
+                    Unit::Millis,
+                    ts * 1000,
+                    Some(1625097600000),
+                ),
+                ("ticks", Timeframe::Ticks(100), Unit::Seconds, ts, None),
+                ("volume", Timeframe::Volume(1e6), Unit::Seconds, ts, None),
+            ];
+
+            for (name, timeframe, unit, ts, expected) in test_cases {
+                assert_eq!(
+                    timeframe.bucket_start(ts, unit),
+                    expected,
+                    "Test case '{}' failed",
+                    name
+                );
+            }
+        }
+
+        #[test]
+        fn test_next_start() {
+            // 2021-12-15 00:00:00 UTC, a Wednesday.
+            let ts = 1639526400;
+
+            let test_cases = vec![
+                ("5m", Timeframe::Minutes(5), ts + 1, Some(ts + 300)),
+                ("1D", Timeframe::Days(1), ts + 1, Some(ts + 86_400)),
+                ("1W", Timeframe::Weeks(1), ts, Some(1639958400)),
+                ("1M", Timeframe::Months(1), ts, Some(1640995200)),
+                ("3M", Timeframe::Months(3), ts, Some(1640995200)),
+                ("ticks", Timeframe::Ticks(100), ts, None),
+            ];
+
+            for (name, timeframe, ts, expected) in test_cases {
+                assert_eq!(
+                    timeframe.next_start(ts, Unit::Seconds),
+                    expected,
+                    "Test case '{}' failed",
+                    name
+                );
+            }
+        }
+
+        #[test]
+        fn test_validate() {
+            let ohlcs = vec![
+                OHLC::new(1.0, 2.0, 0.5, 1.5, 1625097600),
+                OHLC::new(1.0, 2.0, 0.5, 1.5, 1625097660),
+                OHLC::new(1.0, 2.0, 0.5, 1.5, 1625097725),
+            ];
+
+            assert_eq!(
+                validate(&ohlcs, Timeframe::Minutes(1), Unit::Seconds),
+                Err(vec![TimeframeError::Misaligned {
+                    index: 2,
+                    ts: 1625097725
+                }])
+            );
+            assert_eq!(
+                validate(&ohlcs[..2], Timeframe::Minutes(1), Unit::Seconds),
+                Ok(())
+            );
+            assert_eq!(
+                validate(&ohlcs, Timeframe::Ticks(10), Unit::Seconds),
+                Ok(())
+            );
+        }
+
+        #[test]
+        fn test_civil_round_trip() {
+            for days in [-719_468, -1, 0, 18_808, 19_000, 2_932_896] {
+                let (y, m, d) = civil_from_days(days);
+                assert_eq!(
+                    days_from_civil(y, m, d),
+                    days,
+                    "Round trip failed for {}",
+                    days
+                );
+            }
+            assert_eq!(civil_from_days(18_809), (2021, 7, 1));
+        }
+    }
+}
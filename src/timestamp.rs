@@ -0,0 +1,289 @@
+pub mod timestamp {
+    use std::fmt;
+
+    const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+    /// The earliest plausible time for market data, 1971-01-01T00:00:00Z, in seconds.
+    const PLAUSIBLE_FROM: u64 = 31_536_000;
+
+    /// The end of plausible times for market data, 2200-01-01T00:00:00Z, in seconds.
+    const PLAUSIBLE_TO: u64 = 7_258_118_400;
+
+    /// The unit a timestamp is expressed in.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum Unit {
+        Seconds,
+        Millis,
+        Micros,
+        Nanos,
+    }
+
+    impl Unit {
+        /// Number of timestamp ticks in one second.
+        pub fn per_second(&self) -> u64 {
+            match self {
+                Unit::Seconds => 1,
+                Unit::Millis => 1_000,
+                Unit::Micros => 1_000_000,
+                Unit::Nanos => 1_000_000_000,
+            }
+        }
+
+        /// Number of nanoseconds in one timestamp tick.
+        fn nanos(&self) -> u64 {
+            NANOS_PER_SECOND / self.per_second()
+        }
+    }
+
+    impl fmt::Display for Unit {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Unit::Seconds => write!(f, "seconds"),
+                Unit::Millis => write!(f, "milliseconds"),
+                Unit::Micros => write!(f, "microseconds"),
+                Unit::Nanos => write!(f, "nanoseconds"),
+            }
+        }
+    }
+
+    /// A reason a date time could not be converted to a Timestamp.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum TimestampError {
+        /// The date time is before the Unix epoch or too far in the future.
+        OutOfRange,
+    }
+
+    impl fmt::Display for TimestampError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                TimestampError::OutOfRange => {
+                    write!(f, "Timestamp must be between 1970 and 2554")
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for TimestampError {}
+
+    /// A point in time, held as nanoseconds since the Unix epoch.
+    ///
+    /// Values too large to hold in nanoseconds, past the year 2554, saturate.
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct Timestamp(u64);
+
+    impl Timestamp {
+        /// Returns a Timestamp from a value in the given unit.
+        pub fn new(value: u64, unit: Unit) -> Self {
+            Timestamp(value.saturating_mul(unit.nanos()))
+        }
+
+        /// Returns a Timestamp from seconds.
+        pub fn from_secs(secs: u64) -> Self {
+            Self::new(secs, Unit::Seconds)
+        }
+
+        /// Returns a Timestamp from milliseconds.
+        pub fn from_millis(millis: u64) -> Self {
+            Self::new(millis, Unit::Millis)
+        }
+
+        /// Returns a Timestamp from microseconds.
+        pub fn from_micros(micros: u64) -> Self {
+            Self::new(micros, Unit::Micros)
+        }
+
+        /// Returns a Timestamp from nanoseconds.
+        pub fn from_nanos(nanos: u64) -> Self {
+            Timestamp(nanos)
+        }
+
+        /// Returns the value in the given unit, truncating any finer part.
+        pub fn as_unit(&self, unit: Unit) -> u64 {
+            self.0 / unit.nanos()
+        }
+
+        /// Returns the value in whole seconds.
+        pub fn as_secs(&self) -> u64 {
+            self.as_unit(Unit::Seconds)
+        }
+
+        /// Returns the value in whole milliseconds.
+        pub fn as_millis(&self) -> u64 {
+            self.as_unit(Unit::Millis)
+        }
+
+        /// Returns the value in whole microseconds.
+        pub fn as_micros(&self) -> u64 {
+            self.as_unit(Unit::Micros)
+        }
+
+        /// Returns the value in whole nanoseconds.
+        pub fn as_nanos(&self) -> u64 {
+            self.0
+        }
+
+        /// Returns the nanoseconds past the last whole second.
+        pub fn subsec_nanos(&self) -> u32 {
+            (self.0 % NANOS_PER_SECOND) as u32
+        }
+
+        /// Returns true if the Timestamp is between 1971 and 2200.
+        ///
+        /// The window is narrow enough that a value in one unit read as a neighbouring unit is
+        /// never plausible, so this catches seconds mistaken for milliseconds and the like.
+        pub fn is_plausible(&self) -> bool {
+            (PLAUSIBLE_FROM..PLAUSIBLE_TO).contains(&self.as_secs())
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    impl From<Timestamp> for chrono::DateTime<chrono::Utc> {
+        fn from(ts: Timestamp) -> Self {
+            // Every u64 of nanoseconds is well within the range chrono supports.
+            chrono::DateTime::from_timestamp(ts.as_secs() as i64, ts.subsec_nanos())
+                .expect("timestamp within chrono range")
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    impl<Tz: chrono::TimeZone> TryFrom<chrono::DateTime<Tz>> for Timestamp {
+        type Error = TimestampError;
+
+        fn try_from(dt: chrono::DateTime<Tz>) -> Result<Self, Self::Error> {
+            let nanos = dt.timestamp_nanos_opt().ok_or(TimestampError::OutOfRange)?;
+            u64::try_from(nanos)
+                .map(Timestamp)
+                .map_err(|_| TimestampError::OutOfRange)
+        }
+    }
+
+    #[cfg(feature = "time")]
+    impl From<Timestamp> for time::OffsetDateTime {
+        fn from(ts: Timestamp) -> Self {
+            // Every u64 of nanoseconds is well within the range time supports.
+            time::OffsetDateTime::from_unix_timestamp_nanos(ts.0 as i128)
+                .expect("timestamp within time range")
+        }
+    }
+
+    #[cfg(feature = "time")]
+    impl TryFrom<time::OffsetDateTime> for Timestamp {
+        type Error = TimestampError;
+
+        fn try_from(dt: time::OffsetDateTime) -> Result<Self, Self::Error> {
+            u64::try_from(dt.unix_timestamp_nanos())
+                .map(Timestamp)
+                .map_err(|_| TimestampError::OutOfRange)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_conversions() {
+            let test_cases = vec![
+                ("seconds", Timestamp::from_secs(1625097600)),
+                ("millis", Timestamp::from_millis(1625097600000)),
+                ("micros", Timestamp::from_micros(1625097600000000)),
+                ("nanos", Timestamp::from_nanos(1625097600000000000)),
+                ("new", Timestamp::new(1625097600000, Unit::Millis)),
+            ];
+
+            for (name, ts) in test_cases {
+                assert_eq!(ts.as_secs(), 1625097600, "Test case '{}' failed", name);
+                assert_eq!(ts.as_millis(), 1625097600000, "Test case '{}' failed", name);
+                assert_eq!(
+                    ts.as_nanos(),
+                    1625097600000000000,
+                    "Test case '{}' failed",
+                    name
+                );
+            }
+
+            let ts = Timestamp::from_millis(1625097600250);
+            assert_eq!(ts.as_secs(), 1625097600);
+            assert_eq!(ts.subsec_nanos(), 250_000_000);
+            assert!(Timestamp::from_secs(1) < Timestamp::from_millis(1001));
+            assert_eq!(Timestamp::from_secs(u64::MAX).as_nanos(), u64::MAX);
+        }
+
+        #[test]
+        fn test_is_plausible() {
+            let test_cases = vec![
+                ("seconds", Timestamp::new(1625097600, Unit::Seconds), true),
+                ("millis", Timestamp::new(1625097600000, Unit::Millis), true),
+                (
+                    "seconds as millis",
+                    Timestamp::new(1625097600, Unit::Millis),
+                    false,
+                ),
+                (
+                    "millis as seconds",
+                    Timestamp::new(1625097600000, Unit::Seconds),
+                    false,
+                ),
+                (
+                    "micros as millis",
+                    Timestamp::new(1625097600000000, Unit::Millis),
+                    false,
+                ),
+                (
+                    "nanos as micros",
+                    Timestamp::new(1625097600000000000, Unit::Micros),
+                    false,
+                ),
+                ("zero", Timestamp::default(), false),
+            ];
+
+            for (name, ts, expected) in test_cases {
+                assert_eq!(ts.is_plausible(), expected, "Test case '{}' failed", name);
+            }
+        }
+
+        #[cfg(feature = "chrono")]
+        #[test]
+        fn test_chrono() {
+            use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+
+            let ts = Timestamp::from_millis(1625097600250);
+            let dt: DateTime<Utc> = ts.into();
+
+            assert_eq!(dt.to_rfc3339(), "2021-07-01T00:00:00.250+00:00");
+            assert_eq!(Timestamp::try_from(dt), Ok(ts));
+
+            let offset = FixedOffset::east_opt(36_000).unwrap();
+            let local = offset.with_ymd_and_hms(2021, 7, 1, 10, 0, 0).unwrap();
+            assert_eq!(
+                Timestamp::try_from(local),
+                Ok(Timestamp::from_secs(1625097600))
+            );
+
+            let before_epoch = Utc.with_ymd_and_hms(1969, 12, 31, 0, 0, 0).unwrap();
+            assert_eq!(
+                Timestamp::try_from(before_epoch),
+                Err(TimestampError::OutOfRange)
+            );
+        }
+
+        #[cfg(feature = "time")]
+        #[test]
+        fn test_time() {
+            use time::OffsetDateTime;
+
+            let ts = Timestamp::from_micros(1625097600000001);
+            let dt: OffsetDateTime = ts.into();
+
+            assert_eq!(dt.unix_timestamp(), 1625097600);
+            assert_eq!(dt.microsecond(), 1);
+            assert_eq!(Timestamp::try_from(dt), Ok(ts));
+            assert_eq!(
+                Timestamp::try_from(OffsetDateTime::UNIX_EPOCH - time::Duration::SECOND),
+                Err(TimestampError::OutOfRange)
+            );
+        }
+    }
+}
@@ -0,0 +1,233 @@
+pub mod volatility {
+    use crate::indicators::indicators::mean;
+    use crate::ohlc::ohlc::OHLC;
+
+    /// A volatility estimator.
+    ///
+    /// Estimators that use the previous close, close-to-close and Yang-Zhang, treat the first
+    /// OHLC of a slice as the reference and estimate over the rest.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum Estimator {
+        /// Sample standard deviation of log close to close returns.
+        CloseToClose,
+        /// From the high and low, assuming no drift and no opening jumps.
+        Parkinson,
+        /// From all four prices, assuming no drift and no opening jumps.
+        GarmanKlass,
+        /// From all four prices, allowing for drift.
+        RogersSatchell,
+        /// Combines the overnight, open to close and Rogers-Satchell variances, allowing for drift
+        /// and opening jumps.
+        YangZhang,
+    }
+
+    impl Estimator {
+        /// The fewest OHLC the estimator needs.
+        pub fn min_len(&self) -> usize {
+            match self {
+                Estimator::CloseToClose | Estimator::YangZhang => 3,
+                Estimator::Parkinson | Estimator::GarmanKlass | Estimator::RogersSatchell => 1,
+            }
+        }
+
+        /// Returns the variance per OHLC, or None if there are too few OHLC.
+        pub fn variance(&self, ohlcs: &[OHLC]) -> Option<f64> {
+            if ohlcs.len() < self.min_len() {
+                return None;
+            }
+
+            let variance = match self {
+                Estimator::CloseToClose => {
+                    let returns: Vec<f64> = ohlcs
+                        .windows(2)
+                        .map(|w| (w[1].close / w[0].close).ln())
+                        .collect();
+                    sample_variance(&returns)
+                }
+                Estimator::Parkinson => {
+                    let squares: Vec<f64> = ohlcs
+                        .iter()
+                        .map(|ohlc| (ohlc.high / ohlc.low).ln().powi(2))
+                        .collect();
+                    mean(&squares) / (4.0 * std::f64::consts::LN_2)
+                }
+                Estimator::GarmanKlass => {
+                    let terms: Vec<f64> = ohlcs
+                        .iter()
+                        .map(|ohlc| {
+                            let hl = (ohlc.high / ohlc.low).ln();
+                            let co = (ohlc.close / ohlc.open).ln();
+                            0.5 * hl * hl - (2.0 * std::f64::consts::LN_2 - 1.0) * co * co
+                        })
+                        .collect();
+                    mean(&terms)
+                }
+                Estimator::RogersSatchell => rogers_satchell(ohlcs),
+                Estimator::YangZhang => {
+                    let n = (ohlcs.len() - 1) as f64;
+                    let overnight: Vec<f64> = ohlcs
+                        .windows(2)
+                        .map(|w| (w[1].open / w[0].close).ln())
+                        .collect();
+                    let open_close: Vec<f64> = ohlcs[1..]
+                        .iter()
+                        .map(|ohlc| (ohlc.close / ohlc.open).ln())
+                        .collect();
+                    let k = 0.34 / (1.34 + (n + 1.0) / (n - 1.0));
+
+                    sample_variance(&overnight)
+                        + k * sample_variance(&open_close)
+                        + (1.0 - k) * rogers_satchell(&ohlcs[1..])
+                }
+            };
+
+            Some(variance)
+        }
+    }
+
+    /// Annualized volatility of a slice of OHLC.
+    ///
+    /// `periods_per_year` is the number of OHLC in a year, such as 252 for daily OHLC.
+    pub fn volatility(ohlcs: &[OHLC], estimator: Estimator, periods_per_year: f64) -> Option<f64> {
+        estimator
+            .variance(ohlcs)
+            .map(|variance| (variance * periods_per_year).sqrt())
+    }
+
+    /// Annualized volatility over a rolling window of `period` OHLC.
+    ///
+    /// The value at each index is the volatility of the window ending there, so the first value
+    /// is at index `period - 1` provided the window is long enough for the estimator.
+    pub fn rolling_volatility(
+        ohlcs: &[OHLC],
+        estimator: Estimator,
+        period: usize,
+        periods_per_year: f64,
+    ) -> Vec<Option<f64>> {
+        let mut result = vec![None; ohlcs.len()];
+        if period == 0 {
+            return result;
+        }
+
+        for (i, window) in ohlcs.windows(period).enumerate() {
+            result[i + period - 1] = volatility(window, estimator, periods_per_year);
+        }
+
+        result
+    }
+
+    fn sample_variance(values: &[f64]) -> f64 {
+        let avg = mean(values);
+        let sum: f64 = values.iter().map(|v| (v - avg).powi(2)).sum();
+        sum / (values.len() - 1) as f64
+    }
+
+    fn rogers_satchell(ohlcs: &[OHLC]) -> f64 {
+        let terms: Vec<f64> = ohlcs
+            .iter()
+            .map(|ohlc| {
+                let hc = (ohlc.high / ohlc.close).ln();
+                let ho = (ohlc.high / ohlc.open).ln();
+                let lc = (ohlc.low / ohlc.close).ln();
+                let lo = (ohlc.low / ohlc.open).ln();
+                hc * ho + lc * lo
+            })
+            .collect();
+        mean(&terms)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const EPSILON: f64 = 1e-12;
+
+        const ESTIMATORS: [Estimator; 5] = [
+            Estimator::CloseToClose,
+            Estimator::Parkinson,
+            Estimator::GarmanKlass,
+            Estimator::RogersSatchell,
+            Estimator::YangZhang,
+        ];
+
+        fn fixture() -> Vec<OHLC> {
+            vec![
+                OHLC::new(100.0, 102.0, 99.0, 101.0, 1),
+                OHLC::new(101.0, 103.0, 100.0, 102.5, 2),
+                OHLC::new(102.0, 102.8, 100.5, 101.0, 3),
+                OHLC::new(101.5, 104.0, 101.0, 103.5, 4),
+                OHLC::new(103.0, 105.0, 102.5, 104.2, 5),
+            ]
+        }
+
+        #[test]
+        fn test_volatility() {
+            // Reference values calculated independently from the published formulas.
+            let expected = [
+                0.26479694616099325,
+                0.2598457657501753,
+                0.2748244124585428,
+                0.266947244946544,
+                0.25926157614100875,
+            ];
+
+            for (estimator, expected) in ESTIMATORS.iter().zip(expected) {
+                let result = volatility(&fixture(), *estimator, 252.0).unwrap();
+
+                assert!(
+                    (result - expected).abs() < EPSILON,
+                    "{:?}: got {}, expected {}",
+                    estimator,
+                    result,
+                    expected
+                );
+            }
+        }
+
+        #[test]
+        fn test_too_few() {
+            let ohlcs = fixture();
+
+            for estimator in ESTIMATORS {
+                let len = estimator.min_len();
+
+                assert_eq!(volatility(&ohlcs[..len - 1], estimator, 252.0), None);
+                assert!(volatility(&ohlcs[..len], estimator, 252.0).is_some());
+            }
+        }
+
+        #[test]
+        fn test_rolling_volatility() {
+            let ohlcs = fixture();
+            let expected = [
+                0.198800759028033,
+                0.24302654598281234,
+                0.24903611962349367,
+                0.23841242107129504,
+                0.25824599242651103,
+            ];
+
+            for (estimator, expected) in ESTIMATORS.iter().zip(expected) {
+                let result = rolling_volatility(&ohlcs, *estimator, 3, 252.0);
+
+                assert_eq!(result.len(), 5);
+                assert_eq!(result[..2], [None, None], "{:?}", estimator);
+                for (i, value) in result.iter().enumerate().skip(2) {
+                    assert_eq!(
+                        *value,
+                        volatility(&ohlcs[i - 2..=i], *estimator, 252.0),
+                        "{:?} at {}",
+                        estimator,
+                        i
+                    );
+                }
+                assert!((result[4].unwrap() - expected).abs() < EPSILON);
+            }
+
+            assert_eq!(
+                rolling_volatility(&ohlcs, Estimator::Parkinson, 0, 252.0),
+                vec![None; 5]
+            );
+        }
+    }
+}